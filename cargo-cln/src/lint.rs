@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+use macros_core::{Config, Stylesheet};
+
+use crate::scan::{ClassLiteral, ClassUsage};
+
+const SIZE_KEYWORDS: [&str; 22] = [
+    "none", "px", "auto", "full", "screen", "min", "max", "fit", "xs", "sm", "md", "lg", "xl",
+    "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl", "9xl", "base",
+];
+
+const COLOR_KEYWORDS: [&str; 5] = ["inherit", "current", "transparent", "black", "white"];
+
+const COLORS: [&str; 22] = [
+    "slate", "gray", "zinc", "neutral", "stone", "red", "orange", "amber", "yellow", "lime",
+    "green", "emerald", "teal", "cyan", "sky", "blue", "indigo", "violet", "purple", "fuchsia",
+    "pink", "rose",
+];
+
+/// One finding of `cargo cln lint`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Warning {
+    fn new(literal: &ClassLiteral, message: String) -> Self {
+        Self {
+            line: literal.line,
+            column: literal.column,
+            message,
+        }
+    }
+}
+
+/// Splits `class` into its variant modifiers and the utility: `md:p-4` -> (`md:`, `p-4`).
+fn split_variants(class: &str) -> (&str, &str) {
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, c) in class.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => start = index + 1,
+            _ => {}
+        }
+    }
+
+    class.split_at(start)
+}
+
+fn is_value(segment: &str) -> bool {
+    segment.starts_with('[')
+        || SIZE_KEYWORDS.contains(&segment)
+        || segment
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == '/')
+}
+
+/// Key under which two classes set the same property: the variants with the utility
+/// without its value, `md:p-4` -> `md:p`. Colours are values of their own group, so
+/// `bg-red-500` -> `bg-color` while `text-sm` -> `text`. Classes without a value have no key.
+fn conflict_key(class: &str) -> Option<String> {
+    let (variants, utility) = split_variants(class);
+    let utility = utility.trim_start_matches('!').trim_start_matches('-');
+    let (root, value) = utility.rsplit_once('-')?;
+
+    let color_root = match root.rsplit_once('-') {
+        Some((root, color)) if COLORS.contains(&color) && is_value(value) => Some(root),
+        _ => COLOR_KEYWORDS.contains(&value).then_some(root),
+    };
+    if let Some(root) = color_root {
+        return Some(format!("{}{}-color", variants, root));
+    }
+
+    is_value(value).then(|| format!("{}{}", variants, root))
+}
+
+/// Duplicated and conflicting classes among the unconditional classes of one usage.
+pub fn lint_usage(usage: &ClassUsage) -> Vec<Warning> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut keys: HashMap<String, &str> = HashMap::new();
+
+    for (literal, class) in usage.classes().filter(|(literal, _)| !literal.conditional) {
+        if !seen.insert(class) {
+            warnings.push(Warning::new(
+                literal,
+                format!("Duplicate class `{}` in {}", class, usage.name),
+            ));
+            continue;
+        }
+
+        if let Some(key) = conflict_key(class) {
+            if let Some(previous) = keys.insert(key, class) {
+                warnings.push(Warning::new(
+                    literal,
+                    format!(
+                        "Class `{}` conflicts with `{}` in {}",
+                        class, previous, usage.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Classes of one usage that no stylesheet rule defines, after applying `cln.toml`.
+pub fn unknown_classes(
+    usage: &ClassUsage,
+    config: &Config,
+    stylesheet: &Stylesheet,
+) -> Vec<Warning> {
+    let mut warnings: Vec<Warning> = Vec::new();
+
+    for (literal, class) in usage.classes() {
+        let class = config.transform_classes(class);
+        for class in class.split_whitespace() {
+            if stylesheet.is_known(class) {
+                continue;
+            }
+
+            let message = match stylesheet.suggestions(class).as_slice() {
+                [] => format!("Unknown class `{}`", class),
+                suggestions => format!(
+                    "Unknown class `{}`. Did you mean `{}`?",
+                    class,
+                    suggestions.join("`, `")
+                ),
+            };
+            warnings.push(Warning::new(literal, message));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+    use crate::scan::scan_source;
+
+    #[test]
+    fn duplicates_and_conflicts() {
+        let source = r#"fn f(a: bool) { cn!("flex p-2 md:p-4", "flex p-4 bg-red-500", (a, "bg-blue-500")); }"#;
+        let (usages, _) = scan_source(Path::new("lib.rs"), source);
+
+        let messages = lint_usage(&usages[0])
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            messages,
+            [
+                "Duplicate class `flex` in cn!",
+                "Class `p-4` conflicts with `p-2` in cn!",
+            ]
+        );
+    }
+
+    #[test]
+    fn color_and_size_conflicts() {
+        let source = r#"fn f() { cn!("text-sm bg-red-500 text-red-500/50", "text-lg bg-blue-500 text-white md:bg-black"); }"#;
+        let (usages, _) = scan_source(Path::new("lib.rs"), source);
+
+        let messages = lint_usage(&usages[0])
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            messages,
+            [
+                "Class `text-lg` conflicts with `text-sm` in cn!",
+                "Class `bg-blue-500` conflicts with `bg-red-500` in cn!",
+                "Class `text-white` conflicts with `text-red-500/50` in cn!",
+            ]
+        );
+        assert_eq!(
+            conflict_key("hover:border-sky-400").as_deref(),
+            Some("hover:border-color")
+        );
+        assert_eq!(conflict_key("border-2").as_deref(), Some("border"));
+        assert_eq!(conflict_key("font-bold"), None);
+    }
+}
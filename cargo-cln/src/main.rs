@@ -0,0 +1,260 @@
+mod lint;
+mod manifest;
+mod scan;
+mod sort;
+
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use macros_core::{Config, Stylesheet};
+
+use scan::{rust_files, scan_source, ClassUsage};
+
+const USAGE: &str = "\
+Usage: cargo cln <COMMAND> [OPTIONS] [PATH]...
+
+Commands:
+  extract [--locations]                     List every class used in cn! macros and #[class] attributes
+  lint [--stylesheet FILE]... [--safelist CLASS]...
+                                            Report duplicated, conflicting and unknown classes
+  sort [--check]                            Rewrite class literals in Tailwind order
+  safelist [--output FILE] [DIR]            Merge the class manifests written by the macros
+  styles [--output FILE] [DIR]              Merge the stylesheets written by css!
+
+PATH defaults to `src` and DIR to the `[manifest]` directory of `cln.toml`. Stylesheets and
+safelist default to the ones of `cln.toml`.";
+
+struct Args {
+    command: String,
+    flags: Vec<String>,
+    stylesheets: Vec<PathBuf>,
+    safelist: Vec<String>,
+    output: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut command = args.next().ok_or(USAGE)?;
+        // `cargo cln ...` runs `cargo-cln cln ...`.
+        if command == "cln" {
+            command = args.next().ok_or(USAGE)?;
+        }
+
+        let mut parsed = Self {
+            command,
+            flags: Vec::new(),
+            stylesheets: Vec::new(),
+            safelist: Vec::new(),
+            output: None,
+            paths: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--stylesheet" => parsed
+                    .stylesheets
+                    .push(args.next().ok_or("--stylesheet expects a file")?.into()),
+                "--safelist" => parsed
+                    .safelist
+                    .push(args.next().ok_or("--safelist expects a class")?),
+                "--output" => {
+                    parsed.output = Some(args.next().ok_or("--output expects a file")?.into())
+                }
+                flag if flag.starts_with("--") => parsed.flags.push(arg),
+                _ => parsed.paths.push(arg.into()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+fn read_source(file: &Path) -> Result<String, String> {
+    fs::read_to_string(file).map_err(|err| format!("Failed to read {}: {}", file.display(), err))
+}
+
+/// Scans every file under the paths of `args`, printing parse errors to stderr.
+fn scan(args: &Args) -> Result<Vec<(PathBuf, String, Vec<ClassUsage>)>, String> {
+    let paths = match args.paths.is_empty() {
+        true => vec![PathBuf::from("src")],
+        false => args.paths.clone(),
+    };
+    let files = rust_files(&paths).map_err(|err| err.to_string())?;
+    let mut scanned = Vec::new();
+
+    for file in files {
+        let source = read_source(&file)?;
+        let (usages, errors) = scan_source(&file, &source);
+        for error in errors {
+            eprintln!("error: {}", error);
+        }
+        scanned.push((file, source, usages));
+    }
+
+    Ok(scanned)
+}
+
+fn extract(args: &Args) -> Result<bool, String> {
+    let scanned = scan(args)?;
+
+    if args.flag("--locations") {
+        for (file, _, usages) in &scanned {
+            for (literal, class) in usages.iter().flat_map(ClassUsage::classes) {
+                println!(
+                    "{}\t{}:{}:{}",
+                    class,
+                    file.display(),
+                    literal.line,
+                    literal.column
+                );
+            }
+        }
+    } else {
+        let classes = scanned
+            .iter()
+            .flat_map(|(_, _, usages)| usages)
+            .flat_map(ClassUsage::classes)
+            .map(|(_, class)| class)
+            .collect::<BTreeSet<&str>>();
+        for class in classes {
+            println!("{}", class);
+        }
+    }
+
+    Ok(true)
+}
+
+fn lint(args: &Args) -> Result<bool, String> {
+    let config = Config::load(&env::current_dir().map_err(|err| err.to_string())?)?;
+    let mut stylesheets = args.stylesheets.clone();
+    let mut safelist = args.safelist.clone();
+    if stylesheets.is_empty() {
+        stylesheets.clone_from(&config.validation.stylesheets);
+        safelist.extend(config.validation.safelist.iter().cloned());
+    }
+    let stylesheet = match stylesheets.is_empty() {
+        true => None,
+        false => Some(Stylesheet::new(stylesheets, safelist)?),
+    };
+
+    let mut clean = true;
+    for (file, _, usages) in scan(args)? {
+        for usage in &usages {
+            let mut warnings = lint::lint_usage(usage);
+            if let Some(stylesheet) = &stylesheet {
+                warnings.extend(lint::unknown_classes(usage, &config, stylesheet));
+            }
+
+            for warning in warnings {
+                clean = false;
+                println!(
+                    "{}:{}:{}: {}",
+                    file.display(),
+                    warning.line,
+                    warning.column,
+                    warning.message
+                );
+            }
+        }
+    }
+
+    Ok(clean)
+}
+
+fn sort(args: &Args) -> Result<bool, String> {
+    let check = args.flag("--check");
+    let mut clean = true;
+
+    for (file, source, usages) in scan(args)? {
+        let Some(sorted) = sort::sort_source(&source, &usages) else {
+            continue;
+        };
+
+        clean = false;
+        if check {
+            println!("{}: classes are not sorted", file.display());
+        } else {
+            fs::write(&file, sorted)
+                .map_err(|err| format!("Failed to write {}: {}", file.display(), err))?;
+            println!("{}: sorted", file.display());
+        }
+    }
+
+    Ok(clean || !check)
+}
+
+/// Directory the macros write their manifests to: the first path, or the `[manifest]`
+/// directory of `cln.toml`.
+fn manifest_dir(args: &Args) -> Result<PathBuf, String> {
+    match args.paths.first() {
+        Some(dir) => Ok(dir.clone()),
+        None => Config::load(&env::current_dir().map_err(|err| err.to_string())?)?
+            .manifest
+            .map(|manifest| manifest.dir)
+            .ok_or_else(|| "No DIR given and no [manifest] section in cln.toml".to_string()),
+    }
+}
+
+fn safelist(args: &Args) -> Result<bool, String> {
+    let dir = manifest_dir(args)?;
+
+    let result = match &args.output {
+        Some(output) => manifest::write_safelist(&dir, output),
+        None => manifest::merge_manifests(&dir).map(|classes| {
+            for class in classes {
+                println!("{}", class);
+            }
+        }),
+    };
+
+    result
+        .map(|()| true)
+        .map_err(|err| format!("Failed to merge {}: {}", dir.display(), err))
+}
+
+fn styles(args: &Args) -> Result<bool, String> {
+    let dir = manifest_dir(args)?;
+    let styles = manifest::merge_styles(&dir)
+        .map_err(|err| format!("Failed to merge {}: {}", dir.display(), err))?;
+
+    match &args.output {
+        Some(output) => fs::write(output, styles)
+            .map_err(|err| format!("Failed to write {}: {}", output.display(), err))?,
+        None => println!("{}", styles),
+    }
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let result = Args::parse(env::args().skip(1)).and_then(|args| match args.command.as_str() {
+        "extract" => extract(&args),
+        "lint" => lint(&args),
+        "sort" => sort(&args),
+        "safelist" => safelist(&args),
+        "styles" => styles(&args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        command => Err(format!("Unknown command `{}`\n\n{}", command, USAGE)),
+    });
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
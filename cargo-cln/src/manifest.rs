@@ -0,0 +1,93 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use classlist::{ManifestEntry, MANIFEST_EXTENSION, STYLES_EXTENSION};
+
+/// Reads every manifest in `dir` (one file per call site).
+pub fn read_manifests(dir: &Path) -> io::Result<Vec<ManifestEntry>> {
+    let mut entries: Vec<ManifestEntry> = Vec::new();
+
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext == MANIFEST_EXTENSION)
+        {
+            entries.extend(
+                fs::read_to_string(&path)?
+                    .lines()
+                    .filter_map(ManifestEntry::parse),
+            );
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Merges every manifest in `dir` into a sorted, deduplicated list of classes.
+pub fn merge_manifests(dir: &Path) -> io::Result<BTreeSet<String>> {
+    Ok(read_manifests(dir)?
+        .into_iter()
+        .map(|entry| entry.class)
+        .collect())
+}
+
+/// Writes the merged classes of `dir` to `output`, one class per line. The file can be
+/// listed in Tailwind `content` or read into its `safelist`.
+pub fn write_safelist(dir: &Path, output: &Path) -> io::Result<()> {
+    let mut file = fs::File::create(output)?;
+    for class in merge_manifests(dir)? {
+        writeln!(file, "{}", class)?;
+    }
+
+    Ok(())
+}
+
+/// Merges every stylesheet written by `css!` in `dir` (one file per style), dropping
+/// repeated rules.
+pub fn merge_styles(dir: &Path) -> io::Result<String> {
+    let mut rules: BTreeSet<String> = BTreeSet::new();
+
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        if path.extension().is_some_and(|ext| ext == STYLES_EXTENSION) {
+            rules.extend(fs::read_to_string(&path)?.lines().map(str::to_string));
+        }
+    }
+
+    Ok(rules.into_iter().collect::<Vec<String>>().join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_crate_manifests() {
+        let dir = std::env::temp_dir().join(format!("cln-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("first.classes"),
+            "flex\tfirst\tsrc/lib.rs:1:5\nhover:underline\tfirst\tsrc/lib.rs:2:5\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("second.classes"),
+            "flex\tsecond\tsrc/main.rs:3:1\n",
+        )
+        .unwrap();
+        fs::write(dir.join("ignored.txt"), "grid\n").unwrap();
+
+        let classes = merge_manifests(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            classes.into_iter().collect::<Vec<String>>(),
+            vec!["flex", "hover:underline"]
+        );
+    }
+}
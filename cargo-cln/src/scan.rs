@@ -0,0 +1,305 @@
+use std::{
+    borrow::Cow,
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use classlist::expand_variant_groups;
+use macros_core::{class_attribute_tokens, ClassAttribute, CnParser, CnUnit};
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use syn::{
+    parse2, punctuated::Punctuated, visit::Visit, Attribute, ItemEnum, Macro, Path as SynPath,
+    Token,
+};
+
+const CN_MACROS: [&str; 4] = ["cn", "cn_list", "cn_static", "cn_str"];
+
+/// A string with classes inside a `cn!` call or a `#[class]` attribute.
+#[derive(Debug)]
+pub struct ClassLiteral {
+    pub classes: Vec<String>,
+    pub line: usize,
+    pub column: usize,
+    /// Whether the classes come from a `(condition, "if", "else")` tuple.
+    pub conditional: bool,
+    /// Byte range of the string literal in its file, when it can be rewritten in place.
+    pub range: Option<Range<usize>>,
+}
+
+/// Every class literal of one `cn!` call or one `#[derive(Variant)]` arm.
+#[derive(Debug)]
+pub struct ClassUsage {
+    pub name: String,
+    pub literals: Vec<ClassLiteral>,
+}
+
+impl ClassUsage {
+    pub fn classes(&self) -> impl Iterator<Item = (&ClassLiteral, &str)> {
+        self.literals.iter().flat_map(|literal| {
+            literal
+                .classes
+                .iter()
+                .map(move |class| (literal, class.as_str()))
+        })
+    }
+}
+
+fn split_classes(classes: &str) -> Vec<String> {
+    let classes = expand_variant_groups(classes).unwrap_or(Cow::Borrowed(classes));
+    classes.split_whitespace().map(str::to_string).collect()
+}
+
+fn class_literal(classes: &str, span: Span, range: Option<Range<usize>>) -> ClassLiteral {
+    let start = span.start();
+
+    ClassLiteral {
+        classes: split_classes(classes),
+        line: start.line,
+        column: start.column + 1,
+        conditional: false,
+        range,
+    }
+}
+
+fn parse_literals(tokens: TokenStream, source: &str) -> syn::Result<Vec<ClassLiteral>> {
+    let parser = parse2::<CnParser>(tokens)?;
+    let mut literals: Vec<ClassLiteral> = Vec::new();
+
+    for unit in &parser.0 {
+        match unit {
+            CnUnit::Str(literal) => {
+                let range = literal.span().byte_range();
+                let is_plain = source
+                    .get(range.clone())
+                    .is_some_and(|text| text.starts_with('"') && !text.contains('\\'));
+
+                literals.push(class_literal(
+                    &literal.value(),
+                    literal.span(),
+                    is_plain.then_some(range),
+                ));
+            }
+            CnUnit::Tuple(tuple) => {
+                let else_cond = tuple.else_cond.as_deref().unwrap_or_default();
+                literals.push(ClassLiteral {
+                    conditional: true,
+                    ..class_literal(
+                        &format!("{} {}", tuple.if_cond, else_cond),
+                        tuple.span,
+                        None,
+                    )
+                });
+            }
+            CnUnit::Map(map) => literals.push(ClassLiteral {
+                conditional: true,
+                ..class_literal(&map.classes(), map.span, None)
+            }),
+            CnUnit::Int(_) | CnUnit::Ident(_) | CnUnit::Prefixed(_) | CnUnit::Format(_) => {}
+        }
+    }
+
+    Ok(literals)
+}
+
+fn is_cn_macro(path: &SynPath) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| CN_MACROS.contains(&segment.ident.to_string().as_str()))
+}
+
+fn derives_variant(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<SynPath, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Variant")
+        })
+}
+
+struct UsageVisitor<'a> {
+    file: &'a Path,
+    source: &'a str,
+    usages: Vec<ClassUsage>,
+    errors: Vec<String>,
+}
+
+impl UsageVisitor<'_> {
+    fn push(&mut self, name: String, tokens: TokenStream, span: Span) {
+        match parse_literals(tokens, self.source) {
+            Ok(literals) => self.usages.push(ClassUsage { name, literals }),
+            Err(err) => {
+                let start = span.start();
+                self.errors.push(format!(
+                    "{}:{}:{}: {}",
+                    self.file.display(),
+                    start.line,
+                    start.column + 1,
+                    err
+                ));
+            }
+        }
+    }
+
+    /// Finds `cn!(...)` calls nested in the tokens of other macros (`view! { ... }`).
+    fn scan_tokens(&mut self, tokens: TokenStream) {
+        let tokens = tokens.into_iter().collect::<Vec<TokenTree>>();
+
+        for (index, token) in tokens.iter().enumerate() {
+            match (token, tokens.get(index + 1), tokens.get(index + 2)) {
+                (
+                    TokenTree::Ident(ident),
+                    Some(TokenTree::Punct(bang)),
+                    Some(TokenTree::Group(group)),
+                ) if bang.as_char() == '!'
+                    && group.delimiter() != Delimiter::None
+                    && CN_MACROS.contains(&ident.to_string().as_str()) =>
+                {
+                    self.push(format!("{}!", ident), group.stream(), ident.span());
+                }
+                (TokenTree::Group(group), _, _) => self.scan_tokens(group.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UsageVisitor<'_> {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        match mac.path.segments.last() {
+            Some(segment) if is_cn_macro(&mac.path) => {
+                self.push(
+                    format!("{}!", segment.ident),
+                    mac.tokens.clone(),
+                    segment.ident.span(),
+                );
+            }
+            _ => self.scan_tokens(mac.tokens.clone()),
+        }
+
+        syn::visit::visit_macro(self, mac);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast ItemEnum) {
+        if derives_variant(&item.attrs) {
+            for variant in &item.variants {
+                let name = format!("{}::{}", item.ident, variant.ident);
+                let ClassAttribute { units, slots, .. } =
+                    class_attribute_tokens(&variant.attrs).into();
+                if !units.is_empty() {
+                    self.push(name.clone(), units, variant.ident.span());
+                }
+                for (slot, tokens) in slots {
+                    self.push(format!("{}.{}", name, slot), tokens, slot.span());
+                }
+            }
+        }
+
+        syn::visit::visit_item_enum(self, item);
+    }
+}
+
+/// Class usages of one source file, with the messages of calls that failed to parse.
+pub fn scan_source(file: &Path, source: &str) -> (Vec<ClassUsage>, Vec<String>) {
+    let syntax = match syn::parse_file(source) {
+        Ok(syntax) => syntax,
+        Err(err) => return (Vec::new(), vec![format!("{}: {}", file.display(), err)]),
+    };
+
+    let mut visitor = UsageVisitor {
+        file,
+        source,
+        usages: Vec::new(),
+        errors: Vec::new(),
+    };
+    visitor.visit_file(&syntax);
+
+    (visitor.usages, visitor.errors)
+}
+
+/// Every `.rs` file under `paths`, skipping `target` and hidden directories.
+pub fn rust_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut pending = paths.to_vec();
+
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            for entry in fs::read_dir(&path)? {
+                let entry = entry?.path();
+                let name = entry.file_name().unwrap_or_default().to_string_lossy();
+                if name == "target" || name.starts_with('.') {
+                    continue;
+                }
+                pending.push(entry);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        use cln::{cn, Variant};
+
+        #[derive(Variant)]
+        enum Size {
+            #[class("p-1 p-1")]
+            Sm,
+            #[class("p-4", (0, "bold"))]
+            Lg(bool),
+            #[class(root = ("flex", 0), icon = "w-4")]
+            Icon(bool),
+        }
+
+        fn view(active: bool) -> String {
+            html! { <div class={cn!("flex", (active, "ring", "ring-0"))} /> };
+            cln::cn_static!("grid gap-2")
+        }
+    "#;
+
+    #[test]
+    fn finds_macros_and_variants() {
+        let (usages, errors) = scan_source(Path::new("lib.rs"), SOURCE);
+        assert!(errors.is_empty());
+
+        let names = usages
+            .iter()
+            .map(|usage| usage.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            names,
+            [
+                "Size::Sm",
+                "Size::Lg",
+                "Size::Icon.root",
+                "Size::Icon.icon",
+                "cn!",
+                "cn_static!"
+            ]
+        );
+
+        let classes = usages[1]
+            .classes()
+            .map(|(_, class)| class)
+            .collect::<Vec<&str>>();
+        assert_eq!(classes, ["p-4", "bold"]);
+
+        let literal = &usages[5].literals[0];
+        assert_eq!(literal.line, 16);
+        assert_eq!(&SOURCE[literal.range.clone().unwrap()], "\"grid gap-2\"");
+    }
+}
@@ -0,0 +1,56 @@
+use std::ops::Range;
+
+use classlist::{sort_cnl, ClassOrder};
+
+use crate::scan::ClassUsage;
+
+/// New content of `source` with every rewritable class literal of `usages` in Tailwind
+/// order, or `None` when the file is already sorted.
+pub fn sort_source(source: &str, usages: &[ClassUsage]) -> Option<String> {
+    let mut replacements: Vec<(Range<usize>, String)> = usages
+        .iter()
+        .flat_map(|usage| &usage.literals)
+        .filter_map(|literal| {
+            let range = literal.range.clone()?;
+            // Sorting would split variant groups apart.
+            let value = source
+                .get(range.start + 1..range.end - 1)
+                .filter(|value| !value.contains('('))?;
+            let sorted = sort_cnl(value, ClassOrder::Tailwind);
+
+            (sorted != value).then(|| (range, format!("\"{}\"", sorted)))
+        })
+        .collect();
+
+    if replacements.is_empty() {
+        return None;
+    }
+
+    replacements.sort_by_key(|(range, _)| range.start);
+
+    let mut result = source.to_string();
+    for (range, literal) in replacements.into_iter().rev() {
+        result.replace_range(range, &literal);
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+    use crate::scan::scan_source;
+
+    #[test]
+    fn sort_literals_in_place() {
+        let source = r#"fn f(a: bool) { cn!("p-2 hover:underline flex", (a, "b a"), "a\n b"); }"#;
+        let (usages, _) = scan_source(Path::new("lib.rs"), source);
+
+        assert_eq!(
+            sort_source(source, &usages).unwrap(),
+            r#"fn f(a: bool) { cn!("flex p-2 hover:underline", (a, "b a"), "a\n b"); }"#
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{class_list::ClassList, cleanup_cnl::split_cnl};
+
+/// Minimal set of `classList` operations turning one class list into another.
+///
+/// Applying `removed` with `classList.remove` and then `added` with `classList.add`
+/// yields the same class set as the target. `reordered` tells whether the resulting
+/// token order still differs from the target, which only matters to renderers that
+/// need the exact `className` string (snapshots, SSR hydration checks).
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ClassDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub reordered: bool,
+}
+
+impl ClassDiff {
+    /// No class has to be added or removed, though the order may still differ.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.is_empty() && !self.reordered
+    }
+}
+
+fn diff_tokens(prev: &[&str], next: &[&str]) -> ClassDiff {
+    let removed = prev
+        .iter()
+        .filter(|class| !next.contains(class))
+        .map(|class| class.to_string())
+        .collect::<Vec<String>>();
+    let added = next
+        .iter()
+        .filter(|class| !prev.contains(class))
+        .map(|class| class.to_string())
+        .collect::<Vec<String>>();
+
+    let patched = prev
+        .iter()
+        .filter(|class| next.contains(class))
+        .copied()
+        .chain(added.iter().map(String::as_str));
+    let reordered = !patched.eq(next.iter().copied());
+
+    ClassDiff {
+        added,
+        removed,
+        reordered,
+    }
+}
+
+/// Diffs two class strings after the same cleanup `cleanup_cnl` applies.
+pub fn diff_cnl(prev: &str, next: &str) -> ClassDiff {
+    diff_tokens(&split_cnl(prev), &split_cnl(next))
+}
+
+impl ClassList {
+    pub fn diff(&self, next: &ClassList) -> ClassDiff {
+        diff_tokens(
+            &self.iter().collect::<Vec<&str>>(),
+            &next.iter().collect::<Vec<&str>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn added_and_removed() {
+        let diff = diff_cnl("btn btn-sm active", "btn  btn-lg btn active");
+
+        assert_eq!(diff.added, vec!["btn-lg"]);
+        assert_eq!(diff.removed, vec!["btn-sm"]);
+        assert!(diff.reordered);
+    }
+
+    #[test]
+    fn order_only_change() {
+        let diff = ClassList::from("a b c").diff(&ClassList::from("c a b"));
+
+        assert!(diff.is_empty());
+        assert!(diff.reordered);
+        assert_eq!(diff_cnl("a b", "a b c").added, vec!["c"]);
+        assert!(diff_cnl("a b", "a  b").is_unchanged());
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{cleanup_cnl::split_cnl, cn_builder::CnBuilder, into_classes::IntoClasses};
+
+/// Deduplicated list of class tokens.
+///
+/// Tokens keep the order they were added in (the same order `cleanup_cnl` produces),
+/// but equality and hashing ignore it, so `"a b"` and `"b a"` are the same list.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ClassList(Vec<String>);
+
+impl ClassList {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(crate) fn from_unique(classes: Vec<&str>) -> Self {
+        Self(classes.into_iter().map(str::to_string).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, class: &str) -> bool {
+        self.0.iter().any(|item| item == class)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Adds every token of `classes`. An already present token is moved to the end,
+    /// matching the "last one wins" ordering of `cleanup_cnl`.
+    pub fn insert(&mut self, classes: &str) {
+        for class in split_cnl(classes) {
+            if let Some(index) = self.0.iter().position(|item| item == class) {
+                self.0.remove(index);
+            }
+            self.0.push(class.to_string());
+        }
+    }
+
+    pub fn union(&self, other: &ClassList) -> ClassList {
+        let mut result = self.clone();
+        for class in other.iter() {
+            if !result.contains(class) {
+                result.0.push(class.to_string());
+            }
+        }
+
+        result
+    }
+
+    pub fn difference(&self, other: &ClassList) -> ClassList {
+        self.iter().filter(|class| !other.contains(class)).collect()
+    }
+
+    pub fn intersection(&self, other: &ClassList) -> ClassList {
+        self.iter().filter(|class| other.contains(class)).collect()
+    }
+}
+
+impl PartialEq for ClassList {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|class| other.contains(class))
+    }
+}
+
+impl Eq for ClassList {}
+
+impl Hash for ClassList {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut sorted = self.iter().collect::<Vec<&str>>();
+        sorted.sort_unstable();
+        sorted.hash(state);
+    }
+}
+
+impl fmt::Display for ClassList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join(" "))
+    }
+}
+
+impl From<&str> for ClassList {
+    fn from(value: &str) -> Self {
+        let mut list = Self::new();
+        list.insert(value);
+        list
+    }
+}
+
+impl From<String> for ClassList {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<ClassList> for String {
+    fn from(value: ClassList) -> Self {
+        value.to_string()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for ClassList {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for classes in iter {
+            list.insert(classes);
+        }
+        list
+    }
+}
+
+impl IntoIterator for ClassList {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ClassList {
+    type Item = &'a str;
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, String>, fn(&'a String) -> &'a str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+/// The classes are already rendered (by `cn_list!` or [`CnBuilder::to_list`]), so the
+/// prefix is not applied again and former `raw:` classes stay unprefixed.
+impl IntoClasses for ClassList {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        for class in self.iter() {
+            builder.push_prepared(class);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of(list: &ClassList) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        list.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn order_insensitive_equality() {
+        let first = ClassList::from("a b  c a");
+        let second = ClassList::from("c b a");
+
+        assert_eq!(first.to_string(), "b c a");
+        assert!(first == second);
+        assert_eq!(hash_of(&first), hash_of(&second));
+        assert!(first != ClassList::from("a b"));
+    }
+
+    #[test]
+    fn set_operations() {
+        let first = ClassList::from("a b c");
+        let second = ClassList::from("c d");
+
+        assert_eq!(first.union(&second).to_string(), "a b c d");
+        assert_eq!(first.difference(&second).to_string(), "a b");
+        assert_eq!(first.intersection(&second).to_string(), "c");
+        assert!(first.contains("b"));
+        assert!(!first.contains("d"));
+    }
+}
@@ -0,0 +1,644 @@
+use crate::cleanup_cnl::split_tokens;
+use crate::prefix::utility_start;
+
+/// Order of the classes in the generated list.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ClassOrder {
+    /// Classes keep the order they were added in.
+    #[default]
+    Insertion,
+    /// Classes are sorted the way `prettier-plugin-tailwindcss` sorts them: unknown
+    /// classes first, then by variant modifiers, then by the Tailwind property order.
+    Tailwind,
+}
+
+impl ClassOrder {
+    pub fn sort(self, classes: &mut [&str]) {
+        self.sort_prefixed(classes, "");
+    }
+
+    /// Same as [`ClassOrder::sort`] for classes carrying a global prefix (`tw-flex`),
+    /// which is ignored when looking the utility up.
+    pub fn sort_prefixed(self, classes: &mut [&str], prefix: &str) {
+        if self == ClassOrder::Tailwind {
+            classes.sort_by_cached_key(|class| tailwind_key(class, prefix));
+        }
+    }
+}
+
+/// Sorts the classes of `input` in `order`, keeping duplicates.
+pub fn sort_cnl(input: &str, order: ClassOrder) -> String {
+    let mut classes = split_tokens(input).collect::<Vec<&str>>();
+    order.sort(&mut classes);
+
+    classes.join(" ")
+}
+
+enum Rule {
+    /// The whole utility: `flex`.
+    Exact(&'static str),
+    /// The name followed by one of the keywords: `text-sm`.
+    Keywords(&'static str, &'static [&'static str]),
+    /// The name followed by a number, a fraction, `px` or an arbitrary value: `border-2`.
+    Numeric(&'static str),
+    /// The name followed by any value: `bg-red-500`.
+    Prefix(&'static str),
+}
+
+use Rule::{Exact, Keywords, Numeric, Prefix};
+
+const SIZES: [&str; 13] = [
+    "xs", "sm", "base", "md", "lg", "xl", "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl",
+];
+
+/// Tailwind utilities grouped by property, in the order of its core plugins.
+const PROPERTIES: &[&[Rule]] = &[
+    &[Exact("container")],
+    &[Exact("sr-only"), Exact("not-sr-only")],
+    &[Prefix("pointer-events")],
+    &[Exact("visible"), Exact("invisible"), Exact("collapse")],
+    &[
+        Exact("static"),
+        Exact("fixed"),
+        Exact("absolute"),
+        Exact("relative"),
+        Exact("sticky"),
+    ],
+    &[
+        Prefix("inset"),
+        Prefix("inset-x"),
+        Prefix("inset-y"),
+        Prefix("start"),
+        Prefix("end"),
+        Prefix("top"),
+        Prefix("right"),
+        Prefix("bottom"),
+        Prefix("left"),
+    ],
+    &[Exact("isolate"), Exact("isolation-auto")],
+    &[Prefix("z")],
+    &[Prefix("order")],
+    &[Prefix("col")],
+    &[Prefix("row")],
+    &[Prefix("float")],
+    &[Prefix("clear")],
+    &[
+        Prefix("m"),
+        Prefix("mx"),
+        Prefix("my"),
+        Prefix("ms"),
+        Prefix("me"),
+        Prefix("mt"),
+        Prefix("mr"),
+        Prefix("mb"),
+        Prefix("ml"),
+    ],
+    &[Prefix("box")],
+    &[Prefix("line-clamp")],
+    &[
+        Exact("block"),
+        Exact("inline-block"),
+        Exact("inline"),
+        Exact("flex"),
+        Exact("inline-flex"),
+        Exact("table"),
+        Exact("inline-table"),
+        Prefix("table"),
+        Exact("flow-root"),
+        Exact("grid"),
+        Exact("inline-grid"),
+        Exact("contents"),
+        Exact("list-item"),
+        Exact("hidden"),
+    ],
+    &[Prefix("aspect")],
+    &[Prefix("size")],
+    &[Prefix("h")],
+    &[Prefix("max-h")],
+    &[Prefix("min-h")],
+    &[Prefix("w")],
+    &[Prefix("min-w")],
+    &[Prefix("max-w")],
+    &[Keywords("flex", &["1", "auto", "initial", "none"])],
+    &[Exact("shrink"), Prefix("shrink"), Exact("flex-shrink")],
+    &[Exact("grow"), Prefix("grow"), Exact("flex-grow")],
+    &[Prefix("basis")],
+    &[Keywords("table", &["auto", "fixed"])],
+    &[Prefix("caption")],
+    &[Keywords("border", &["collapse", "separate"])],
+    &[Prefix("border-spacing")],
+    &[Prefix("origin")],
+    &[Prefix("translate-x"), Prefix("translate-y")],
+    &[Prefix("rotate")],
+    &[Prefix("skew-x"), Prefix("skew-y")],
+    &[Prefix("scale"), Prefix("scale-x"), Prefix("scale-y")],
+    &[
+        Exact("transform"),
+        Exact("transform-cpu"),
+        Exact("transform-gpu"),
+        Exact("transform-none"),
+    ],
+    &[Prefix("animate")],
+    &[Prefix("cursor")],
+    &[Prefix("touch")],
+    &[Prefix("select")],
+    &[Prefix("resize"), Exact("resize")],
+    &[Prefix("snap")],
+    &[
+        Prefix("scroll-m"),
+        Prefix("scroll-mx"),
+        Prefix("scroll-my"),
+        Prefix("scroll-mt"),
+        Prefix("scroll-mr"),
+        Prefix("scroll-mb"),
+        Prefix("scroll-ml"),
+    ],
+    &[
+        Prefix("scroll-p"),
+        Prefix("scroll-px"),
+        Prefix("scroll-py"),
+        Prefix("scroll-pt"),
+        Prefix("scroll-pr"),
+        Prefix("scroll-pb"),
+        Prefix("scroll-pl"),
+    ],
+    &[Keywords("list", &["inside", "outside"])],
+    &[Prefix("list")],
+    &[Prefix("list-image")],
+    &[Prefix("appearance")],
+    &[Prefix("columns")],
+    &[
+        Prefix("break-before"),
+        Prefix("break-inside"),
+        Prefix("break-after"),
+    ],
+    &[Prefix("auto-cols")],
+    &[Prefix("grid-flow")],
+    &[Prefix("auto-rows")],
+    &[Prefix("grid-cols")],
+    &[Prefix("grid-rows")],
+    &[Keywords(
+        "flex",
+        &["row", "row-reverse", "col", "col-reverse"],
+    )],
+    &[Keywords("flex", &["wrap", "wrap-reverse", "nowrap"])],
+    &[Prefix("place-content")],
+    &[Prefix("place-items")],
+    &[Keywords(
+        "content",
+        &[
+            "normal", "center", "start", "end", "between", "around", "evenly", "baseline",
+            "stretch",
+        ],
+    )],
+    &[Prefix("items")],
+    &[Prefix("justify")],
+    &[Prefix("justify-items")],
+    &[Prefix("gap"), Prefix("gap-x"), Prefix("gap-y")],
+    &[Prefix("space-x"), Prefix("space-y")],
+    &[
+        Exact("divide-x"),
+        Numeric("divide-x"),
+        Exact("divide-y"),
+        Numeric("divide-y"),
+    ],
+    &[Keywords(
+        "divide",
+        &["solid", "dashed", "dotted", "double", "none"],
+    )],
+    &[Prefix("divide")],
+    &[Prefix("place-self")],
+    &[Prefix("self")],
+    &[Prefix("justify-self")],
+    &[
+        Prefix("overflow"),
+        Prefix("overflow-x"),
+        Prefix("overflow-y"),
+    ],
+    &[Prefix("overscroll")],
+    &[Keywords("scroll", &["auto", "smooth"])],
+    &[
+        Exact("truncate"),
+        Exact("text-ellipsis"),
+        Exact("text-clip"),
+    ],
+    &[Prefix("hyphens")],
+    &[Prefix("whitespace")],
+    &[Keywords("text", &["wrap", "nowrap", "balance", "pretty"])],
+    &[Keywords("break", &["normal", "words", "all", "keep"])],
+    &[Exact("rounded"), Prefix("rounded")],
+    &[
+        Exact("border"),
+        Numeric("border"),
+        Exact("border-x"),
+        Numeric("border-x"),
+        Exact("border-y"),
+        Numeric("border-y"),
+        Exact("border-s"),
+        Numeric("border-s"),
+        Exact("border-e"),
+        Numeric("border-e"),
+        Exact("border-t"),
+        Numeric("border-t"),
+        Exact("border-r"),
+        Numeric("border-r"),
+        Exact("border-b"),
+        Numeric("border-b"),
+        Exact("border-l"),
+        Numeric("border-l"),
+    ],
+    &[Keywords(
+        "border",
+        &["solid", "dashed", "dotted", "double", "hidden", "none"],
+    )],
+    &[
+        Prefix("border"),
+        Prefix("border-x"),
+        Prefix("border-y"),
+        Prefix("border-s"),
+        Prefix("border-e"),
+        Prefix("border-t"),
+        Prefix("border-r"),
+        Prefix("border-b"),
+        Prefix("border-l"),
+    ],
+    &[Prefix("border-opacity")],
+    &[Prefix("bg")],
+    &[Prefix("bg-opacity")],
+    &[Exact("bg-none"), Prefix("bg-gradient")],
+    &[Prefix("from"), Prefix("via"), Prefix("to")],
+    &[Prefix("box-decoration")],
+    &[Keywords("bg", &["auto", "cover", "contain"])],
+    &[Keywords("bg", &["fixed", "local", "scroll"])],
+    &[Prefix("bg-clip")],
+    &[Keywords(
+        "bg",
+        &[
+            "bottom",
+            "center",
+            "left",
+            "left-bottom",
+            "left-top",
+            "right",
+            "right-bottom",
+            "right-top",
+            "top",
+        ],
+    )],
+    &[Keywords(
+        "bg",
+        &[
+            "repeat",
+            "no-repeat",
+            "repeat-x",
+            "repeat-y",
+            "repeat-round",
+            "repeat-space",
+        ],
+    )],
+    &[Prefix("bg-origin")],
+    &[Prefix("fill")],
+    &[Prefix("stroke")],
+    &[Numeric("stroke")],
+    &[Keywords(
+        "object",
+        &["contain", "cover", "fill", "none", "scale-down"],
+    )],
+    &[Prefix("object")],
+    &[
+        Prefix("p"),
+        Prefix("px"),
+        Prefix("py"),
+        Prefix("ps"),
+        Prefix("pe"),
+        Prefix("pt"),
+        Prefix("pr"),
+        Prefix("pb"),
+        Prefix("pl"),
+    ],
+    &[Keywords(
+        "text",
+        &["left", "center", "right", "justify", "start", "end"],
+    )],
+    &[Prefix("indent")],
+    &[Prefix("align")],
+    &[Keywords("font", &["sans", "serif", "mono"])],
+    &[Keywords(
+        "text",
+        &[
+            "xs", "sm", "base", "lg", "xl", "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl", "9xl",
+        ],
+    )],
+    &[Keywords(
+        "font",
+        &[
+            "thin",
+            "extralight",
+            "light",
+            "normal",
+            "medium",
+            "semibold",
+            "bold",
+            "extrabold",
+            "black",
+        ],
+    )],
+    &[
+        Exact("uppercase"),
+        Exact("lowercase"),
+        Exact("capitalize"),
+        Exact("normal-case"),
+    ],
+    &[Exact("italic"), Exact("not-italic")],
+    &[
+        Exact("normal-nums"),
+        Exact("ordinal"),
+        Exact("slashed-zero"),
+        Exact("lining-nums"),
+        Exact("oldstyle-nums"),
+        Exact("proportional-nums"),
+        Exact("tabular-nums"),
+        Exact("diagonal-fractions"),
+        Exact("stacked-fractions"),
+    ],
+    &[Prefix("leading")],
+    &[Prefix("tracking")],
+    &[Prefix("text")],
+    &[Prefix("text-opacity")],
+    &[
+        Exact("underline"),
+        Exact("overline"),
+        Exact("line-through"),
+        Exact("no-underline"),
+    ],
+    &[Prefix("decoration")],
+    &[Keywords(
+        "decoration",
+        &["solid", "double", "dotted", "dashed", "wavy"],
+    )],
+    &[
+        Numeric("decoration"),
+        Keywords("decoration", &["auto", "from-font"]),
+    ],
+    &[Prefix("underline-offset")],
+    &[Exact("antialiased"), Exact("subpixel-antialiased")],
+    &[Prefix("placeholder")],
+    &[Prefix("caret")],
+    &[Prefix("accent")],
+    &[Prefix("opacity")],
+    &[Prefix("bg-blend")],
+    &[Prefix("mix-blend")],
+    &[
+        Exact("shadow"),
+        Keywords("shadow", &["sm", "md", "lg", "xl", "2xl", "inner", "none"]),
+    ],
+    &[Prefix("shadow")],
+    &[
+        Exact("outline-none"),
+        Exact("outline"),
+        Keywords("outline", &["dashed", "dotted", "double"]),
+    ],
+    &[Numeric("outline")],
+    &[Prefix("outline-offset")],
+    &[Prefix("outline")],
+    &[Exact("ring"), Numeric("ring"), Exact("ring-inset")],
+    &[Prefix("ring")],
+    &[Prefix("ring-opacity")],
+    &[Numeric("ring-offset")],
+    &[Prefix("ring-offset")],
+    &[Exact("blur"), Prefix("blur")],
+    &[Prefix("brightness")],
+    &[Prefix("contrast")],
+    &[Exact("drop-shadow"), Prefix("drop-shadow")],
+    &[Exact("grayscale"), Prefix("grayscale")],
+    &[Prefix("hue-rotate")],
+    &[Exact("invert"), Prefix("invert")],
+    &[Prefix("saturate")],
+    &[Exact("sepia"), Prefix("sepia")],
+    &[Exact("filter"), Exact("filter-none")],
+    &[Prefix("backdrop")],
+    &[Exact("transition"), Prefix("transition")],
+    &[Prefix("delay")],
+    &[Prefix("duration")],
+    &[Prefix("ease")],
+    &[Prefix("will-change")],
+    &[Prefix("content")],
+];
+
+/// Tailwind variants in the order of its variant plugins. Entries ending with `-*`
+/// match every variant starting with that prefix (`group-hover`, `data-[open]`).
+const VARIANTS: &[&str] = &[
+    "*",
+    "first-letter",
+    "first-line",
+    "marker",
+    "selection",
+    "file",
+    "placeholder",
+    "backdrop",
+    "before",
+    "after",
+    "first",
+    "last",
+    "only",
+    "odd",
+    "even",
+    "first-of-type",
+    "last-of-type",
+    "only-of-type",
+    "visited",
+    "target",
+    "open",
+    "default",
+    "checked",
+    "indeterminate",
+    "placeholder-shown",
+    "autofill",
+    "optional",
+    "required",
+    "valid",
+    "invalid",
+    "in-range",
+    "out-of-range",
+    "read-only",
+    "empty",
+    "focus-within",
+    "hover",
+    "focus",
+    "focus-visible",
+    "active",
+    "enabled",
+    "disabled",
+    "group-*",
+    "peer-*",
+    "has-*",
+    "aria-*",
+    "data-*",
+    "supports-*",
+    "ltr",
+    "rtl",
+    "motion-safe",
+    "motion-reduce",
+    "dark",
+    "print",
+    "sm",
+    "md",
+    "lg",
+    "xl",
+    "2xl",
+    "min-*",
+    "max-*",
+    "portrait",
+    "landscape",
+    "contrast-more",
+    "contrast-less",
+    "forced-colors",
+];
+
+fn is_numeric(value: &str) -> bool {
+    value == "px"
+        || value.starts_with('[')
+        || (!value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == '/'))
+}
+
+/// How closely `rule` matches `utility`, `None` when it does not match at all.
+fn specificity(rule: &Rule, utility: &str) -> Option<(u8, usize)> {
+    let value = |name: &str| {
+        utility
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('-'))
+    };
+
+    match rule {
+        Exact(name) => (utility == *name).then_some((4, name.len())),
+        Keywords(name, keywords) => value(name)
+            .filter(|value| keywords.contains(value))
+            .map(|_| (3, name.len())),
+        Numeric(name) => value(name)
+            .filter(|value| is_numeric(value))
+            .map(|_| (2, name.len())),
+        Prefix(name) => value(name).map(|_| (1, name.len())),
+    }
+}
+
+fn rule_name(rule: &Rule) -> &'static str {
+    match rule {
+        Exact(name) | Keywords(name, _) | Numeric(name) | Prefix(name) => name,
+    }
+}
+
+/// Index of the property and of the rule inside it that best match `utility`.
+fn property_position(utility: &str) -> Option<(usize, usize)> {
+    let mut best: Option<((u8, usize), (usize, usize))> = None;
+
+    for (property, rules) in PROPERTIES.iter().enumerate() {
+        for (index, rule) in rules.iter().enumerate() {
+            let Some(specificity) = specificity(rule, utility) else {
+                continue;
+            };
+            if !matches!(best, Some((best, _)) if best >= specificity) {
+                best = Some((specificity, (property, index)));
+            }
+        }
+    }
+
+    best.map(|(_, position)| position)
+}
+
+/// Rank of a value inside one property: numbers ascending, then sizes from `xs`, then
+/// everything else.
+fn value_rank(value: &str) -> (u8, u64) {
+    let number = match value.split_once('/') {
+        Some((numerator, denominator)) => numerator
+            .parse::<f64>()
+            .ok()
+            .zip(denominator.parse::<f64>().ok())
+            .map(|(numerator, denominator)| numerator / denominator),
+        None if value == "px" => Some(0.25),
+        None => value.parse::<f64>().ok(),
+    };
+
+    // Bit patterns of non-negative floats order like the floats themselves.
+    match (number, SIZES.iter().position(|size| *size == value)) {
+        (Some(number), _) if number >= 0.0 => (0, number.to_bits()),
+        (_, Some(size)) => (1, size as u64),
+        _ => (2, 0),
+    }
+}
+
+fn variant_rank(variant: &str) -> usize {
+    VARIANTS
+        .iter()
+        .position(|known| match known.strip_suffix('*') {
+            Some(prefix) if !prefix.is_empty() => variant.starts_with(prefix),
+            _ => variant == *known,
+        })
+        .unwrap_or(VARIANTS.len())
+}
+
+type TailwindKey = Option<(Vec<usize>, usize, usize, (u8, u64), String)>;
+
+/// Sort key of one class. Unknown classes have no key and stay first in their
+/// original order.
+fn tailwind_key(class: &str, prefix: &str) -> TailwindKey {
+    let start = utility_start(class);
+    let (variants, utility) = class.split_at(start);
+    let utility = utility.trim_start_matches('!').trim_start_matches('-');
+    let utility = utility.strip_prefix(prefix).unwrap_or(utility);
+
+    let (property, rule) = property_position(utility)?;
+    let value = utility
+        .strip_prefix(rule_name(&PROPERTIES[property][rule]))
+        .and_then(|rest| rest.strip_prefix('-'))
+        .unwrap_or_default();
+
+    // Like Tailwind's variant bit mask: the latest variant decides first.
+    let mut variant_ranks = variants
+        .split_terminator(':')
+        .map(variant_rank)
+        .collect::<Vec<usize>>();
+    variant_ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    Some((
+        variant_ranks,
+        property,
+        rule,
+        value_rank(value),
+        utility.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tailwind_order() {
+        let sorted = sort_cnl(
+            "md:p-4 text-white hover:bg-red-500 custom p-2 flex bg-blue-500 text-sm p-1 mt-4 dark:text-black md:flex",
+            ClassOrder::Tailwind,
+        );
+
+        assert_eq!(
+            sorted,
+            "custom mt-4 flex bg-blue-500 p-1 p-2 text-sm text-white hover:bg-red-500 dark:text-black md:flex md:p-4"
+        );
+    }
+
+    #[test]
+    fn prefix_is_ignored() {
+        let mut classes = ["md:tw-flex", "tw-p-2", "tw-flex", "host"];
+        ClassOrder::Tailwind.sort_prefixed(&mut classes, "tw-");
+
+        assert_eq!(classes, ["host", "tw-flex", "tw-p-2", "md:tw-flex"]);
+    }
+
+    #[test]
+    fn insertion_order_is_kept() {
+        assert_eq!(sort_cnl("b  a c", ClassOrder::Insertion), "b a c");
+    }
+}
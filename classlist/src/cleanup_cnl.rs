@@ -1,27 +1,59 @@
-fn find_in(input: &Vec<&str>, str_chunk: &str) -> Option<usize> {
+fn find_in(input: &[&str], str_chunk: &str) -> Option<usize> {
     input.iter().position(|s| *s == str_chunk)
 }
 
-pub fn cleanup_cnl<T>(input: T) -> String
-where
-    T: Into<String>,
-{
-    let input: String = input.into();
-    if input.is_empty() {
-        return "".to_string();
-    }
+pub(crate) fn split_tokens(input: &str) -> impl Iterator<Item = &str> {
+    input.split(' ').map(|s| s.trim()).filter(|s| !s.is_empty())
+}
+
+/// Which occurrence of a repeated class decides its position in the list.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum MergeStrategy {
+    /// The class moves to its last occurrence (`"a b a"` -> `"b a"`).
+    #[default]
+    Last,
+    /// The class stays at its first occurrence (`"a b a"` -> `"a b"`).
+    First,
+}
 
+pub(crate) fn split_cnl_with(input: &str, strategy: MergeStrategy) -> Vec<&str> {
     let mut strings_list: Vec<&str> = Vec::new();
-    let array = input.split(" ").map(|s| s.trim()).filter(|s| !s.is_empty());
+    let array = split_tokens(input);
 
     for str_chunk in array {
         if let Some(founded_index) = find_in(&strings_list, str_chunk) {
+            if strategy == MergeStrategy::First {
+                continue;
+            }
             strings_list.remove(founded_index);
         }
         strings_list.push(str_chunk);
     }
 
-    if strings_list.len() == 0 {
+    strings_list
+}
+
+pub(crate) fn split_cnl(input: &str) -> Vec<&str> {
+    split_cnl_with(input, MergeStrategy::Last)
+}
+
+pub fn cleanup_cnl_with(input: &str, strategy: MergeStrategy) -> String {
+    split_cnl_with(input, strategy).join(" ")
+}
+
+pub fn cleanup_cnl<T>(input: T) -> String
+where
+    T: Into<String>,
+{
+    let input: String = input.into();
+    if input.is_empty() {
+        return "".to_string();
+    }
+
+    let strings_list = split_cnl(&input);
+
+    if strings_list.is_empty() {
         return "".to_string();
     }
 
@@ -44,4 +76,10 @@ mod test {
         let line = cleanup_cnl("first second    second third first  fourth".to_string());
         assert_eq!(line, "second third first fourth")
     }
+
+    #[test]
+    fn keep_first_occurrence() {
+        let line = cleanup_cnl_with("first second second third first", MergeStrategy::First);
+        assert_eq!(line, "first second third")
+    }
 }
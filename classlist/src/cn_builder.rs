@@ -1,51 +1,77 @@
-use crate::cleanup_cnl::cleanup_cnl;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::class_list::ClassList;
+use crate::class_order::ClassOrder;
+use crate::cleanup_cnl::{split_tokens, MergeStrategy};
+use crate::into_classes::IntoClasses;
+use crate::modifiers::{important_classes, prefix_classes};
+use crate::prefix::{apply_prefix, has_raw_class};
+use crate::variant_group::expand_variant_groups;
+
+/// Token count above which duplicates are found with hash sets instead of scanning.
+const HASHED_DEDUP_MIN_TOKENS: usize = 32;
 
 
 #[cfg_attr(feature = "debug", derive(Debug))]
-pub struct CnPart(String);
+pub struct CnPart(String, bool);
 
 impl CnPart {
     pub fn new(cn: String) -> Self {
-        Self(cn)
+        Self(cn, false)
+    }
+
+    /// Classes that already went through a builder, such as the ones of a derived
+    /// `Variant`. They are added with [`CnBuilder::push_prepared`].
+    pub fn prepared(cn: String) -> Self {
+        Self(cn, true)
+    }
+
+    /// Puts every class under `variants`: `CnPart::from("p-4").prefixed("md:")` -> `md:p-4`.
+    pub fn prefixed(self, variants: &str) -> Self {
+        Self(prefix_classes(&self.0, variants), self.1)
+    }
+
+    /// Adds the Tailwind `!` modifier to every class.
+    pub fn important(self) -> Self {
+        Self(important_classes(&self.0), self.1)
     }
 }
 
 impl From<&str> for CnPart {
     fn from(value: &str) -> Self {
-        Self(value.to_string())
+        Self::new(value.to_string())
     }
 }
 
 impl From<String> for CnPart {
     fn from(value: String) -> Self {
-        Self(value)
+        Self::new(value)
     }
 }
 
-
-
-impl<T> From<Option<T>> for CnPart where T: Into<CnPart> + Default {
+impl<T> From<Option<T>> for CnPart
+where
+    T: Into<CnPart> + Default,
+{
     fn from(value: Option<T>) -> Self {
         value.unwrap_or_default().into()
     }
 }
 
-
 impl From<(bool, &str, &str)> for CnPart {
     fn from(value: (bool, &str, &str)) -> Self {
         match value.0 {
-            true => Self(value.1.to_string()),
-            false => Self(value.2.to_string()),
+            true => Self::new(value.1.to_string()),
+            false => Self::new(value.2.to_string()),
         }
     }
 }
 
 impl From<(Option<bool>, &str, &str)> for CnPart {
     fn from(value: (Option<bool>, &str, &str)) -> Self {
-        match value.0 {
-            Some(bool_value) => CnPart::from((bool_value, value.1, value.2)),
-            None => CnPart::from((false, value.1, value.2)),
-        }
+        Self::from((value.0.unwrap_or(false), value.1, value.2))
     }
 }
 
@@ -61,26 +87,363 @@ impl From<(Option<bool>, &str)> for CnPart {
     }
 }
 
+impl IntoClasses for CnPart {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        match self.1 {
+            true => builder.push_prepared(&self.0),
+            false => builder.push(&self.0),
+        }
+    }
+}
+
 
+/// Accumulates classes and deduplicates them once the list is requested.
+///
+/// Segments added through [`CnBuilder::add_static`] are expected to be already
+/// cleaned and unique among themselves (the `cn!` macro does this at compile time),
+/// so only the dynamic contributions have to be compared against the rest.
+///
+/// With a [`CnBuilder::prefix`], every class of a dynamic part gets the prefix after
+/// its variants and important marker, except the ones marked with `raw:`.
+#[derive(Default)]
 #[cfg_attr(feature = "debug", derive(Debug))]
-pub struct CnBuilder(String);
+pub struct CnBuilder {
+    classes: String,
+    dynamic: Vec<Range<usize>>,
+    /// Number of [`CnBuilder::add_static`] segments.
+    static_segments: usize,
+    strategy: MergeStrategy,
+    order: ClassOrder,
+    prefix: Option<&'static str>,
+}
 
 impl CnBuilder {
     pub fn new() -> Self {
-        Self(String::new())
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            classes: String::with_capacity(capacity),
+            dynamic: Vec::new(),
+            static_segments: 0,
+            strategy: MergeStrategy::default(),
+            order: ClassOrder::default(),
+            prefix: None,
+        }
+    }
+
+    /// Empty builder with the same settings, to collect the classes of a wrapped part.
+    pub(crate) fn nested(&self) -> Self {
+        Self {
+            prefix: self.prefix,
+            ..Self::new()
+        }
+    }
+
+    pub fn strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.strategy = strategy;
+
+        self
     }
 
+    pub fn order(mut self, order: ClassOrder) -> Self {
+        self.order = order;
+
+        self
+    }
+
+    pub fn prefix(mut self, prefix: &'static str) -> Self {
+        self.prefix = Some(prefix).filter(|prefix| !prefix.is_empty());
+
+        self
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn add<T>(mut self, item: T) -> Self
     where
-        T: Into<CnPart>,
+        T: IntoClasses,
     {
-        let item: CnPart = item.into();
-        self.0.push_str(&format!(" {}", item.0));
+        item.append_to(&mut self);
+
+        self
+    }
+
+    /// Adds a pre-cleaned (prefixed and sorted) segment that is not deduplicated against
+    /// other static segments.
+    pub fn add_static(mut self, classes: &'static str) -> Self {
+        if !classes.is_empty() {
+            self.classes.push(' ');
+            self.classes.push_str(classes);
+            self.static_segments += 1;
+        }
+
+        self
+    }
+
+    /// Adds a dynamic part with [`CnBuilder::push_prepared`].
+    pub fn add_prepared(mut self, classes: &str) -> Self {
+        self.push_prepared(classes);
 
         self
     }
 
+    /// Adds a dynamic part. Variant groups are expanded, malformed ones are kept as written,
+    /// and the prefix is applied. A string rendered by `cn!` has lost its `raw:` markers, so
+    /// nest a [`ClassList`] or the builder itself instead.
+    pub fn push(&mut self, classes: &str) {
+        let classes = match classes.contains('(') {
+            true => expand_variant_groups(classes).unwrap_or(Cow::Borrowed(classes)),
+            false => Cow::Borrowed(classes),
+        };
+
+        match self.prefix {
+            Some(prefix) => self.push_prepared(&apply_prefix(&classes, prefix)),
+            None if has_raw_class(&classes) => self.push_prepared(&apply_prefix(&classes, "")),
+            None => self.push_prepared(&classes),
+        }
+    }
+
+    /// Adds a dynamic part whose classes already went through [`CnBuilder::push`] of a
+    /// builder with the same prefix, such as the classes of a derived `Variant`.
+    pub fn push_prepared(&mut self, classes: &str) {
+        if classes.is_empty() {
+            return;
+        }
+
+        self.classes.push(' ');
+        let start = self.classes.len();
+        self.classes.push_str(classes);
+        self.dynamic.push(start..self.classes.len());
+    }
+
+    fn segments(&self) -> Vec<(&str, bool)> {
+        let mut segments: Vec<(&str, bool)> = Vec::new();
+        let mut cursor = 0;
+
+        for range in &self.dynamic {
+            segments.push((&self.classes[cursor..range.start], false));
+            segments.push((&self.classes[range.clone()], true));
+            cursor = range.end;
+        }
+        segments.push((&self.classes[cursor..], false));
+
+        segments
+    }
+
+    /// Tokens of every segment, flagged when they come from a dynamic part.
+    fn flagged_tokens(&self) -> Vec<(&str, bool)> {
+        self.segments()
+            .into_iter()
+            .flat_map(|(segment, is_dynamic)| {
+                split_tokens(segment).map(move |token| (token, is_dynamic))
+            })
+            .collect()
+    }
+
+    pub(crate) fn tokens(&self) -> Vec<&str> {
+        let tokens = self.flagged_tokens();
+
+        let mut tokens = match tokens.len() > HASHED_DEDUP_MIN_TOKENS {
+            true => self.dedup_hashed(&tokens),
+            false => self.dedup_scanned(&tokens),
+        };
+        self.order
+            .sort_prefixed(&mut tokens, self.prefix.unwrap_or_default());
+
+        tokens
+    }
+
+    /// Drops the tokens repeated on the side the strategy discards, when either copy is
+    /// dynamic, by scanning the other tokens.
+    fn dedup_scanned<'a>(&self, tokens: &[(&'a str, bool)]) -> Vec<&'a str> {
+        tokens
+            .iter()
+            .enumerate()
+            .filter(|(index, (token, is_dynamic))| {
+                let others = match self.strategy {
+                    MergeStrategy::Last => &tokens[index + 1..],
+                    MergeStrategy::First => &tokens[..*index],
+                };
+
+                !others
+                    .iter()
+                    .any(|(other, other_dynamic)| (*is_dynamic || *other_dynamic) && other == token)
+            })
+            .map(|(_, (token, _))| *token)
+            .collect()
+    }
+
+    /// Same as [`Self::dedup_scanned`], with hash sets of the tokens already visited.
+    fn dedup_hashed<'a>(&self, tokens: &[(&'a str, bool)]) -> Vec<&'a str> {
+        let mut seen: HashSet<&str> = HashSet::with_capacity(tokens.len());
+        let mut seen_dynamic: HashSet<&str> = HashSet::new();
+        let keep = |&(token, is_dynamic): &(&'a str, bool)| {
+            let repeated = seen_dynamic.contains(token) || (is_dynamic && seen.contains(token));
+            seen.insert(token);
+            if is_dynamic {
+                seen_dynamic.insert(token);
+            }
+
+            (!repeated).then_some(token)
+        };
+
+        match self.strategy {
+            MergeStrategy::First => tokens.iter().filter_map(keep).collect(),
+            MergeStrategy::Last => {
+                let mut kept = tokens.iter().rev().filter_map(keep).collect::<Vec<&str>>();
+                kept.reverse();
+                kept
+            }
+        }
+    }
+
     pub fn to_classlist(&self) -> String {
-        cleanup_cnl(&self.0)
+        // A single static segment is already sorted.
+        let sorted = self.order == ClassOrder::Insertion || self.static_segments <= 1;
+        if self.dynamic.is_empty() && sorted {
+            return self.classes.trim_start().to_string();
+        }
+
+        self.tokens().join(" ")
+    }
+
+    pub fn to_list(&self) -> ClassList {
+        ClassList::from_unique(self.tokens())
+    }
+}
+
+impl IntoClasses for CnBuilder {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        builder.push_prepared(&self.to_classlist());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parts_convert_with_into() {
+        let parts: Vec<CnPart> = vec![
+            "a".into(),
+            Some("b").into(),
+            None::<&str>.into(),
+            (true, "c", "d").into(),
+            (Some(false), "e").into(),
+        ];
+        let line = parts
+            .into_iter()
+            .fold(CnBuilder::new(), CnBuilder::add)
+            .to_classlist();
+
+        assert_eq!(line, "a b c");
+    }
+
+    #[test]
+    fn static_segments_are_kept() {
+        let line = CnBuilder::new().add_static("a b").add_static("c").to_classlist();
+
+        assert_eq!(line, "a b c");
+    }
+
+    #[test]
+    fn dynamic_parts_are_deduplicated() {
+        let builder = CnBuilder::with_capacity(16)
+            .add_static("a b")
+            .add("b c")
+            .add_static("d")
+            .add(None::<&str>)
+            .add("c");
+
+        assert_eq!(builder.to_classlist(), "a b d c");
+        assert_eq!(builder.to_list().to_string(), "a b d c");
+    }
+
+    #[test]
+    fn nested_output_is_not_prefixed_again() {
+        let inner = CnBuilder::new().prefix("tw-").add("raw:js-hook flex");
+
+        let nested = CnBuilder::new().prefix("tw-").add(&inner).add("p-2");
+        assert_eq!(nested.to_classlist(), "js-hook tw-flex tw-p-2");
+
+        let list = CnBuilder::new()
+            .prefix("tw-")
+            .add(inner.to_list())
+            .add("grid");
+        assert_eq!(list.to_classlist(), "js-hook tw-flex tw-grid");
+
+        let outer = CnBuilder::new().prefix("tw-").add(nested).add("m-1");
+        assert_eq!(outer.to_classlist(), "js-hook tw-flex tw-p-2 tw-m-1");
+    }
+
+    #[test]
+    fn long_lists_are_deduplicated_with_hash_sets() {
+        let classes = (0..40)
+            .map(|n| format!("c-{}", n % 12))
+            .collect::<Vec<String>>();
+        for strategy in [MergeStrategy::Last, MergeStrategy::First] {
+            let builder = classes
+                .chunks(5)
+                .fold(CnBuilder::new().strategy(strategy), |builder, chunk| {
+                    builder.add_static("c-1 c-2").add(chunk.join(" "))
+                });
+            let tokens = builder.flagged_tokens();
+            assert!(tokens.len() > HASHED_DEDUP_MIN_TOKENS);
+
+            let hashed = builder.dedup_hashed(&tokens);
+            assert!(hashed == builder.dedup_scanned(&tokens));
+            assert!(hashed == builder.tokens());
+        }
+    }
+
+    #[test]
+    fn first_occurrence_strategy() {
+        let line = CnBuilder::new()
+            .strategy(MergeStrategy::First)
+            .add_static("a b")
+            .add("c a")
+            .add("b d")
+            .to_classlist();
+
+        assert_eq!(line, "a b c d");
+    }
+
+    #[test]
+    fn dynamic_variant_groups() {
+        let line = CnBuilder::new()
+            .add_static("hover:underline")
+            .add("hover:(underline text-white)")
+            .add("md:(p-2")
+            .to_classlist();
+
+        assert_eq!(line, "hover:underline hover:text-white md:(p-2");
+    }
+
+    #[test]
+    fn tailwind_order_covers_dynamic_parts() {
+        let line = CnBuilder::new()
+            .order(ClassOrder::Tailwind)
+            .add_static("p-4 flex")
+            .add("hover:underline mt-2")
+            .to_classlist();
+
+        assert_eq!(line, "mt-2 flex p-4 hover:underline");
+    }
+
+    #[test]
+    fn static_segments_are_sorted_once() {
+        let builder = || CnBuilder::new().order(ClassOrder::Tailwind);
+
+        // Sorted at compile time already.
+        assert_eq!(builder().add_static("p-4 flex").to_classlist(), "p-4 flex");
+        assert_eq!(
+            builder()
+                .add_static("mt-2 p-4")
+                .add(None::<&str>)
+                .add_static("flex")
+                .to_classlist(),
+            "mt-2 flex p-4"
+        );
     }
 }
@@ -0,0 +1,130 @@
+use crate::cn_builder::CnBuilder;
+
+/// Anything that can contribute classes to a [`CnBuilder`].
+///
+/// This is the trait `cn!` targets for every non-literal unit, so implementing it
+/// for your own types makes them usable inside `cn!(...)`.
+pub trait IntoClasses {
+    fn append_to(&self, builder: &mut CnBuilder);
+}
+
+/// Condition used by the `(condition, "if", "else")` tuple units.
+pub trait Condition {
+    fn is_active(&self) -> bool;
+}
+
+impl Condition for bool {
+    fn is_active(&self) -> bool {
+        *self
+    }
+}
+
+impl Condition for Option<bool> {
+    fn is_active(&self) -> bool {
+        self.unwrap_or(false)
+    }
+}
+
+impl<T> Condition for &T
+where
+    T: Condition + ?Sized,
+{
+    fn is_active(&self) -> bool {
+        (**self).is_active()
+    }
+}
+
+impl IntoClasses for str {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        builder.push(self);
+    }
+}
+
+impl IntoClasses for String {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        builder.push(self);
+    }
+}
+
+impl<T> IntoClasses for &T
+where
+    T: IntoClasses + ?Sized,
+{
+    fn append_to(&self, builder: &mut CnBuilder) {
+        (**self).append_to(builder);
+    }
+}
+
+impl<T> IntoClasses for Option<T>
+where
+    T: IntoClasses,
+{
+    fn append_to(&self, builder: &mut CnBuilder) {
+        if let Some(value) = self {
+            value.append_to(builder);
+        }
+    }
+}
+
+impl<C> IntoClasses for (C, &str, &str)
+where
+    C: Condition,
+{
+    fn append_to(&self, builder: &mut CnBuilder) {
+        match self.0.is_active() {
+            true => builder.push(self.1),
+            false => builder.push(self.2),
+        }
+    }
+}
+
+impl<C> IntoClasses for (C, &str)
+where
+    C: Condition,
+{
+    fn append_to(&self, builder: &mut CnBuilder) {
+        if self.0.is_active() {
+            builder.push(self.1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Size(u8);
+
+    impl IntoClasses for Size {
+        fn append_to(&self, builder: &mut CnBuilder) {
+            builder.push(&format!("p-{}", self.0));
+        }
+    }
+
+    #[test]
+    fn builtin_units() {
+        let is_active: Option<bool> = None;
+        let line = CnBuilder::new()
+            .add("first")
+            .add("second".to_string())
+            .add(Some("third"))
+            .add(None::<&str>)
+            .add((true, "yes", "no"))
+            .add((is_active, "active", "inactive"))
+            .add((&false, "hidden"))
+            .to_classlist();
+
+        assert_eq!(line, "first second third yes inactive");
+    }
+
+    #[test]
+    fn custom_implementation() {
+        let line = CnBuilder::new()
+            .add("block")
+            .add(Size(4))
+            .add(Size(2))
+            .to_classlist();
+
+        assert_eq!(line, "block p-4 p-2");
+    }
+}
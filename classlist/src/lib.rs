@@ -1,6 +1,51 @@
+mod class_diff;
+mod class_list;
+mod class_order;
 mod cleanup_cnl;
 mod cn_builder;
+mod into_classes;
+mod manifest;
+mod modifiers;
+mod prefix;
+#[cfg(feature = "serde")]
+mod serialize;
+mod styles;
+mod variant;
+mod variant_group;
 
+pub use class_diff::diff_cnl;
+pub use class_diff::ClassDiff;
+pub use class_list::ClassList;
+pub use class_order::sort_cnl;
+pub use class_order::ClassOrder;
 pub use cleanup_cnl::cleanup_cnl;
+pub use cleanup_cnl::cleanup_cnl_with;
+pub use cleanup_cnl::MergeStrategy;
 pub use cn_builder::CnBuilder;
 pub use cn_builder::CnPart;
+pub use into_classes::Condition;
+pub use into_classes::IntoClasses;
+pub use manifest::ManifestEntry;
+pub use manifest::MANIFEST_EXTENSION;
+pub use modifiers::important_classes;
+pub use modifiers::prefix_classes;
+pub use modifiers::Important;
+pub use modifiers::Prefixed;
+pub use modifiers::Responsive;
+pub use prefix::prefix_class;
+pub use prefix::RAW_MARKER;
+#[cfg(feature = "serde")]
+pub use serialize::as_tokens;
+#[cfg(feature = "serde")]
+pub use serialize::ClassTokens;
+pub use styles::collect_style_tag;
+pub use styles::collect_styles;
+pub use styles::register_style;
+pub use styles::registered_styles;
+pub use styles::style_tag;
+pub use styles::ScopedClass;
+pub use styles::Style;
+pub use styles::STYLES_EXTENSION;
+pub use variant::ParseVariantError;
+pub use variant::Variant;
+pub use variant_group::expand_variant_groups;
@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Extension of the per-call-site manifest files written by the macros.
+pub const MANIFEST_EXTENSION: &str = "classes";
+
+/// One literal class seen by the macros: `class<TAB>crate<TAB>file:line:column`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ManifestEntry {
+    pub class: String,
+    pub crate_name: String,
+    pub location: String,
+}
+
+impl fmt::Display for ManifestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t{}", self.class, self.crate_name, self.location)
+    }
+}
+
+impl ManifestEntry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, '\t');
+        let class = parts.next().filter(|class| !class.is_empty())?;
+
+        Some(Self {
+            class: class.to_string(),
+            crate_name: parts.next().unwrap_or_default().to_string(),
+            location: parts.next().unwrap_or_default().to_string(),
+        })
+    }
+}
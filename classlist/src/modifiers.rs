@@ -0,0 +1,195 @@
+use crate::cleanup_cnl::split_tokens;
+use crate::cn_builder::CnBuilder;
+use crate::into_classes::IntoClasses;
+use crate::prefix::utility_start;
+
+/// Puts every class of `classes` under `variants`: `("p-4 flex", "md:")` ->
+/// `md:p-4 md:flex`. The trailing `:` of `variants` is optional.
+pub fn prefix_classes(classes: &str, variants: &str) -> String {
+    let variants = variants.trim_end_matches(':');
+    if variants.is_empty() {
+        return split_tokens(classes).collect::<Vec<&str>>().join(" ");
+    }
+
+    split_tokens(classes)
+        .map(|class| format!("{}:{}", variants, class))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Adds the Tailwind `!` modifier to every class, after its variants: `md:p-4` -> `md:!p-4`.
+pub fn important_classes(classes: &str) -> String {
+    split_tokens(classes)
+        .map(|class| {
+            let (variants, utility) = class.split_at(utility_start(class));
+            match utility.starts_with('!') {
+                true => class.to_string(),
+                false => format!("{}!{}", variants, utility),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Classes of `item`, prefixed like the ones of `builder`.
+fn collect_classes<T: IntoClasses + ?Sized>(item: &T, builder: &CnBuilder) -> String {
+    let mut nested = builder.nested();
+    item.append_to(&mut nested);
+
+    nested.to_classlist()
+}
+
+/// Classes of `T` applied under variant modifiers (`md:`, `hover:`, `md:hover:`).
+/// `cn!(md: size)` expands to this wrapper.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Prefixed<'a, T> {
+    variants: &'a str,
+    item: T,
+}
+
+impl<'a, T> Prefixed<'a, T> {
+    pub fn new(variants: &'a str, item: T) -> Self {
+        Self { variants, item }
+    }
+}
+
+impl<T: IntoClasses> IntoClasses for Prefixed<'_, T> {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        let classes = collect_classes(&self.item, builder);
+        builder.push_prepared(&prefix_classes(&classes, self.variants));
+    }
+}
+
+/// Classes of `T` with the Tailwind `!` (important) modifier.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Important<T>(pub T);
+
+impl<T: IntoClasses> IntoClasses for Important<T> {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        let classes = collect_classes(&self.0, builder);
+        builder.push_prepared(&important_classes(&classes));
+    }
+}
+
+/// One value per breakpoint, expanded to `sm:`/`md:`/`lg:`... prefixed classes.
+///
+/// ```ignore
+/// let padding = Responsive::new(Size::Sm).md(Size::Md).xl(Size::Lg);
+/// cn!("block", padding); // "block p-1 md:p-2 xl:p-4"
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Responsive<T> {
+    base: Option<T>,
+    breakpoints: Vec<(&'static str, T)>,
+}
+
+impl<T> Default for Responsive<T> {
+    fn default() -> Self {
+        Self {
+            base: None,
+            breakpoints: Vec::new(),
+        }
+    }
+}
+
+impl<T> Responsive<T> {
+    /// Value applied without breakpoint.
+    pub fn new(base: T) -> Self {
+        Self {
+            base: Some(base),
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// Value applied from a custom breakpoint on.
+    pub fn at(mut self, breakpoint: &'static str, value: T) -> Self {
+        self.breakpoints.push((breakpoint, value));
+
+        self
+    }
+
+    pub fn sm(self, value: T) -> Self {
+        self.at("sm", value)
+    }
+
+    pub fn md(self, value: T) -> Self {
+        self.at("md", value)
+    }
+
+    pub fn lg(self, value: T) -> Self {
+        self.at("lg", value)
+    }
+
+    pub fn xl(self, value: T) -> Self {
+        self.at("xl", value)
+    }
+
+    pub fn xxl(self, value: T) -> Self {
+        self.at("2xl", value)
+    }
+}
+
+impl<T: IntoClasses> IntoClasses for Responsive<T> {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        if let Some(base) = &self.base {
+            base.append_to(builder);
+        }
+
+        for (breakpoint, value) in &self.breakpoints {
+            Prefixed::new(breakpoint, value).append_to(builder);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cn_builder::CnPart;
+
+    #[test]
+    fn prefix_and_important() {
+        assert_eq!(
+            prefix_classes("p-4  flex", "md:hover:"),
+            "md:hover:p-4 md:hover:flex"
+        );
+        assert_eq!(
+            important_classes("p-4 md:!m-1 md:m-2"),
+            "!p-4 md:!m-1 md:!m-2"
+        );
+
+        let line = CnBuilder::new()
+            .add_static("flex")
+            .add(Prefixed::new("md", "p-4 hover:underline"))
+            .add(Important(Some("m-0")))
+            .add(CnPart::from("gap-2").prefixed("lg:").important())
+            .to_classlist();
+
+        assert_eq!(line, "flex md:p-4 md:hover:underline !m-0 lg:!gap-2");
+    }
+
+    #[test]
+    fn responsive_values() {
+        let line = CnBuilder::new()
+            .add(Responsive::new("p-1").md("p-2").xxl("p-4 m-1"))
+            .add(Responsive::default().lg("grid"))
+            .to_classlist();
+
+        assert_eq!(line, "p-1 md:p-2 2xl:p-4 2xl:m-1 lg:grid");
+    }
+
+    #[test]
+    fn wrapped_parts_are_prefixed_once() {
+        let line = CnBuilder::new()
+            .prefix("tw-")
+            .add_static("tw-flex")
+            .add("hover:!p-2 raw:js-toggle")
+            .add(Prefixed::new("md:", "p-4 raw:host"))
+            .add(Important("m-0"))
+            .to_classlist();
+
+        assert_eq!(
+            line,
+            "tw-flex hover:!tw-p-2 js-toggle md:tw-p-4 md:host !tw-m-0"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::cleanup_cnl::split_tokens;
+
+/// Byte index where the utility part of `class` starts: after the last variant
+/// modifier (`md:hover:`) that is not inside an arbitrary value (`[...]`).
+pub(crate) fn utility_start(class: &str) -> usize {
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, c) in class.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => start = index + 1,
+            _ => {}
+        }
+    }
+
+    start
+}
+
+/// Marks a class as raw: it is emitted without the marker and never prefixed
+/// (`raw:js-toggle`, `md:raw:host-grid`).
+pub const RAW_MARKER: &str = "raw:";
+
+/// `variants` without a trailing raw marker, if it ends with one: `md:` of `md:raw:`.
+fn raw_variants(variants: &str) -> Option<&str> {
+    variants
+        .strip_suffix(RAW_MARKER)
+        .filter(|variants| variants.is_empty() || variants.ends_with(':'))
+}
+
+/// Whether a class of `classes` is marked raw, right before its utility.
+pub(crate) fn has_raw_class(classes: &str) -> bool {
+    classes.contains(RAW_MARKER)
+        && split_tokens(classes).any(|class| raw_variants(&class[..utility_start(class)]).is_some())
+}
+
+/// Adds `prefix` to the utility part of a single class, keeping variant modifiers
+/// and the important marker in front: `hover:!bg-red` -> `hover:!tw-bg-red`.
+/// Raw classes only lose their marker.
+pub fn prefix_class(class: &str, prefix: &str) -> String {
+    let start = utility_start(class);
+    let (variants, utility) = class.split_at(start);
+
+    if let Some(variants) = raw_variants(variants) {
+        return format!("{}{}", variants, utility);
+    }
+    let (important, utility) = match utility.strip_prefix('!') {
+        Some(utility) => ("!", utility),
+        None => ("", utility),
+    };
+
+    if prefix.is_empty() || utility.is_empty() {
+        return class.to_string();
+    }
+
+    format!("{}{}{}{}", variants, important, prefix, utility)
+}
+
+/// [`prefix_class`] applied to every class of `classes`.
+pub(crate) fn apply_prefix(classes: &str, prefix: &str) -> String {
+    split_tokens(classes)
+        .map(|class| prefix_class(class, prefix))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefix_after_variants() {
+        assert_eq!(prefix_class("flex", "tw-"), "tw-flex");
+        assert_eq!(prefix_class("md:hover:bg-red", "tw-"), "md:hover:tw-bg-red");
+        assert_eq!(
+            prefix_class("hover:!underline", "tw-"),
+            "hover:!tw-underline"
+        );
+        assert_eq!(
+            prefix_class("[&:nth-child(3)]:p-1", "tw-"),
+            "[&:nth-child(3)]:tw-p-1"
+        );
+        assert_eq!(prefix_class("tw-flex", "tw-"), "tw-tw-flex");
+        assert_eq!(prefix_class("raw:js-toggle", "tw-"), "js-toggle");
+        assert_eq!(prefix_class("md:raw:host", "tw-"), "md:host");
+        assert_eq!(prefix_class("raw:js-toggle", ""), "js-toggle");
+        assert_eq!(prefix_class("draw:p-1", "tw-"), "draw:tw-p-1");
+    }
+
+    #[test]
+    fn raw_classes() {
+        assert!(has_raw_class("flex raw:js-toggle"));
+        assert!(has_raw_class("md:raw:host"));
+        assert!(!has_raw_class("draw:p-1 raw:md:p-2 flex"));
+        assert!(!has_raw_class("bg-[url(raw:x)]"));
+    }
+}
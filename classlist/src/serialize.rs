@@ -0,0 +1,111 @@
+use std::fmt;
+
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{class_list::ClassList, cn_builder::CnBuilder};
+
+/// Class lists serialize as one string (`"px-4 py-2"`). Use [`as_tokens`] for an
+/// array of tokens instead.
+impl Serialize for ClassList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Serializes the output of the builder, as [`CnBuilder::to_classlist`] renders it.
+impl Serialize for CnBuilder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_classlist())
+    }
+}
+
+struct ClassListVisitor;
+
+impl<'de> Visitor<'de> for ClassListVisitor {
+    type Value = ClassList;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a class string or an array of class tokens")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(ClassList::from(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut list = ClassList::new();
+        while let Some(classes) = seq.next_element::<String>()? {
+            list.insert(&classes);
+        }
+
+        Ok(list)
+    }
+}
+
+/// Accepts both forms: a class string or an array of tokens.
+impl<'de> Deserialize<'de> for ClassList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ClassListVisitor)
+    }
+}
+
+/// Values [`as_tokens`] can serialize.
+pub trait ClassTokens {
+    fn class_tokens(&self) -> Vec<&str>;
+}
+
+impl ClassTokens for ClassList {
+    fn class_tokens(&self) -> Vec<&str> {
+        self.iter().collect()
+    }
+}
+
+impl ClassTokens for CnBuilder {
+    fn class_tokens(&self) -> Vec<&str> {
+        self.tokens()
+    }
+}
+
+/// `#[serde(with = "cln::as_tokens")]`: serializes a [`ClassList`] or the output of a
+/// [`CnBuilder`] as an array of tokens (`["px-4", "py-2"]`). Deserializes both forms
+/// into a [`ClassList`].
+pub mod as_tokens {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::ClassTokens;
+    use crate::class_list::ClassList;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ClassTokens + ?Sized,
+        S: Serializer,
+    {
+        serializer.collect_seq(value.class_tokens())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ClassList, D::Error> {
+        ClassList::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::de::{
+        value::{Error, SeqDeserializer, StrDeserializer},
+        IntoDeserializer,
+    };
+
+    #[test]
+    fn deserializes_strings_and_tokens() {
+        let from_str = ClassList::deserialize(StrDeserializer::<Error>::new("a b a")).unwrap();
+        let tokens: SeqDeserializer<_, Error> = vec!["b", "c d"].into_deserializer();
+        let from_tokens = ClassList::deserialize(tokens).unwrap();
+
+        assert_eq!(from_str.to_string(), "b a");
+        assert_eq!(from_tokens.to_string(), "b c d");
+    }
+}
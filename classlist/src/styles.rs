@@ -0,0 +1,275 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{cn_builder::CnBuilder, into_classes::IntoClasses};
+
+/// Extension of the per-style stylesheets written by `css!` at build time.
+pub const STYLES_EXTENSION: &str = "css";
+
+type Styles = Vec<(&'static str, &'static str)>;
+
+static STYLES: Mutex<Styles> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Rules used by the [`collect_styles`] renders running on this thread, innermost last.
+    static COLLECTORS: RefCell<Vec<Styles>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Class generated by `css!` or `css_module!`. The name already carries the configured
+/// prefix, so builders add it as is instead of prefixing it again.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ScopedClass(&'static str);
+
+impl ScopedClass {
+    pub const fn new(class: &'static str) -> Self {
+        Self(class)
+    }
+
+    pub const fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Deref for ScopedClass {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl AsRef<str> for ScopedClass {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Display for ScopedClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl PartialEq<&str> for ScopedClass {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<ScopedClass> for &'static str {
+    fn from(value: ScopedClass) -> Self {
+        value.0
+    }
+}
+
+impl IntoClasses for ScopedClass {
+    fn append_to(&self, builder: &mut CnBuilder) {
+        builder.push_prepared(self.0);
+    }
+}
+
+/// Scoped style generated by `css!`: a unique class and the rules using it.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Style {
+    class: &'static str,
+    css: &'static str,
+    registered: AtomicBool,
+}
+
+impl Style {
+    pub const fn new(class: &'static str, css: &'static str) -> Self {
+        Self {
+            class,
+            css,
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    pub fn class(&self) -> &'static str {
+        self.class
+    }
+
+    pub fn css(&self) -> &'static str {
+        self.css
+    }
+
+    /// Adds the rules to the global registry, once, and to the render collecting styles
+    /// on this thread, if any. Returns the class.
+    pub fn register(&self) -> &'static str {
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            register_style(self.class, self.css);
+        }
+        COLLECTORS.with(|collectors| {
+            if let Some(styles) = collectors.borrow_mut().last_mut() {
+                push_style(styles, self.class, self.css);
+            }
+        });
+
+        self.class
+    }
+}
+
+fn push_style(styles: &mut Styles, class: &'static str, css: &'static str) {
+    if !styles.iter().any(|(registered, _)| *registered == class) {
+        styles.push((class, css));
+    }
+}
+
+fn join_styles(styles: &[(&'static str, &'static str)]) -> String {
+    styles
+        .iter()
+        .map(|(_, css)| *css)
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Adds the rules of `class` to the global registry. Classes already registered are
+/// skipped, so identical `css!` blocks share one entry.
+pub fn register_style(class: &'static str, css: &'static str) {
+    let mut styles = STYLES.lock().unwrap_or_else(|err| err.into_inner());
+    push_style(&mut styles, class, css);
+}
+
+/// Every registered rule, in registration order.
+pub fn registered_styles() -> String {
+    let styles = STYLES.lock().unwrap_or_else(|err| err.into_inner());
+
+    join_styles(&styles)
+}
+
+/// Pops the styles of a [`collect_styles`] render, even if the render panics, and adds
+/// them to the enclosing render.
+struct Collector;
+
+impl Collector {
+    fn start() -> Self {
+        COLLECTORS.with(|collectors| collectors.borrow_mut().push(Vec::new()));
+
+        Self
+    }
+
+    fn styles(&self) -> String {
+        COLLECTORS.with(|collectors| join_styles(collectors.borrow().last().unwrap()))
+    }
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        COLLECTORS.with(|collectors| {
+            let mut collectors = collectors.borrow_mut();
+            let styles = collectors.pop().unwrap_or_default();
+            if let Some(outer) = collectors.last_mut() {
+                for (class, css) in styles {
+                    push_style(outer, class, css);
+                }
+            }
+        });
+    }
+}
+
+/// Runs a server-side render and returns the rules of every `css!` style it used, in
+/// order. Each render collects its own rules, so renders running concurrently on other
+/// threads do not see them. The render has to run on the calling thread.
+pub fn collect_styles<R>(render: impl FnOnce() -> R) -> (R, String) {
+    let collector = Collector::start();
+    let result = render();
+
+    (result, collector.styles())
+}
+
+/// `styles` wrapped in a `<style>` tag.
+fn tag(styles: String) -> String {
+    format!("<style data-cln>{}</style>", styles.replace("</", "<\\/"))
+}
+
+/// Registered rules wrapped in a `<style>` tag, for server-side rendering.
+pub fn style_tag() -> String {
+    tag(registered_styles())
+}
+
+/// [`collect_styles`] wrapped in a `<style>` tag.
+pub fn collect_style_tag<R>(render: impl FnOnce() -> R) -> (R, String) {
+    let (result, styles) = collect_styles(render);
+
+    (result, tag(styles))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{sync::Barrier, thread};
+
+    #[test]
+    fn styles_are_registered_once() {
+        static CARD: Style = Style::new("css-card", ".css-card{padding:4px}");
+        static SAME: Style = Style::new("css-card", ".css-card{padding:4px}");
+
+        assert_eq!(CARD.register(), "css-card");
+        assert_eq!(SAME.register(), "css-card");
+        register_style("css-tag", ".css-tag::after{content:\"</style>\"}");
+
+        let styles = registered_styles();
+        assert_eq!(styles.matches(".css-card{").count(), 1);
+        assert!(style_tag().contains("content:\"<\\/style>\""));
+
+        let (class, tag) = collect_style_tag(|| CARD.register());
+        assert_eq!(class, "css-card");
+        assert_eq!(tag, "<style data-cln>.css-card{padding:4px}</style>");
+        assert_eq!(collect_styles(|| ()).1, "");
+    }
+
+    #[test]
+    fn scoped_classes_are_not_prefixed() {
+        let class = ScopedClass::new("tw-css-1234abcd");
+        let line = CnBuilder::new()
+            .prefix("tw-")
+            .add("flex")
+            .add(class)
+            .to_classlist();
+
+        assert_eq!(line, "tw-flex tw-css-1234abcd");
+        assert_eq!(class.to_string(), "tw-css-1234abcd");
+        assert_eq!(class, "tw-css-1234abcd");
+    }
+
+    #[test]
+    fn overlapping_renders_collect_their_own_styles() {
+        static SHARED: Style = Style::new("css-shared", ".css-shared{margin:0}");
+        static FIRST: Style = Style::new("css-first", ".css-first{color:red}");
+        static SECOND: Style = Style::new("css-second", ".css-second{color:blue}");
+        static BARRIER: Barrier = Barrier::new(2);
+
+        let render = |own: &'static Style| {
+            move || {
+                collect_styles(|| {
+                    SHARED.register();
+                    // Both renders are running from here on.
+                    BARRIER.wait();
+                    let ((), nested) = collect_styles(|| {
+                        own.register();
+                    });
+                    BARRIER.wait();
+
+                    nested
+                })
+            }
+        };
+        let first = thread::spawn(render(&FIRST));
+        let second = thread::spawn(render(&SECOND));
+
+        let (nested, styles) = first.join().unwrap();
+        assert_eq!(nested, ".css-first{color:red}");
+        assert_eq!(styles, ".css-shared{margin:0}\n.css-first{color:red}");
+        let (nested, styles) = second.join().unwrap();
+        assert_eq!(nested, ".css-second{color:blue}");
+        assert_eq!(styles, ".css-shared{margin:0}\n.css-second{color:blue}");
+        assert_eq!(registered_styles().matches(".css-shared{").count(), 1);
+    }
+}
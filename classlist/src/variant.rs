@@ -0,0 +1,71 @@
+use std::{error::Error, fmt};
+
+use crate::into_classes::IntoClasses;
+
+/// Reflection implemented by `#[derive(Variant)]`, for documentation and safelisting.
+pub trait Variant: IntoClasses {
+    /// Name of every variant, in declaration order.
+    const VARIANT_NAMES: &'static [&'static str];
+
+    /// Every literal class the enum can produce: the classes, slots and value maps of
+    /// every variant, with both branches of conditional tuples. Classes only known at
+    /// runtime are not included.
+    fn all_static_classes() -> &'static [&'static str];
+
+    /// Name of the variant of `self`.
+    fn variant_name(&self) -> &'static str;
+}
+
+/// Error of the `FromStr` implementation generated by `#[class(from_str)]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseVariantError {
+    value: String,
+    valid: &'static [&'static str],
+}
+
+impl ParseVariantError {
+    pub fn new(value: &str, valid: &'static [&'static str]) -> Self {
+        Self {
+            value: value.to_string(),
+            valid,
+        }
+    }
+
+    /// The string that matched no variant.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Names accepted by the parser.
+    pub fn valid_values(&self) -> &'static [&'static str] {
+        self.valid
+    }
+}
+
+impl fmt::Display for ParseVariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown variant {:?}. Valid values: {}",
+            self.value,
+            self.valid.join(", ")
+        )
+    }
+}
+
+impl Error for ParseVariantError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_error_lists_valid_values() {
+        let error = ParseVariantError::new("huge", &["sm", "lg"]);
+
+        assert_eq!(
+            error.to_string(),
+            r#"Unknown variant "huge". Valid values: sm, lg"#
+        );
+    }
+}
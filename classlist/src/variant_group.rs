@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+/// Index of the `)` closing the group opened at `open`, ignoring parentheses inside
+/// arbitrary values (`w-[calc(100%-1rem)]`).
+fn group_end(input: &str, open: usize) -> Result<usize, String> {
+    let mut parens = 0usize;
+    let mut brackets = 0usize;
+
+    for (index, byte) in input.bytes().enumerate().skip(open) {
+        match byte {
+            b'[' => brackets += 1,
+            b']' => brackets = brackets.saturating_sub(1),
+            b'(' if brackets == 0 => parens += 1,
+            b')' if brackets == 0 => {
+                parens -= 1;
+                if parens == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(format!("Unclosed variant group `{}`", &input[open..]))
+}
+
+fn expand_into(input: &str, variants: &str, classes: &mut Vec<String>) -> Result<(), String> {
+    let bytes = input.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index].is_ascii_whitespace() {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        let mut brackets = 0usize;
+        // Parentheses in a value, like the CSS variable of `bg-(--brand)`.
+        let mut parens = 0usize;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'[' => brackets += 1,
+                b']' => brackets = brackets.saturating_sub(1),
+                b'(' if brackets == 0 && (index == start || bytes[index - 1] == b':') => break,
+                b'(' if brackets == 0 => parens += 1,
+                b')' if brackets == 0 && parens == 0 => break,
+                b')' if brackets == 0 => parens -= 1,
+                byte if brackets == 0 && parens == 0 && byte.is_ascii_whitespace() => break,
+                _ => {}
+            }
+            index += 1;
+        }
+
+        let class = &input[start..index];
+        match bytes.get(index) {
+            Some(b'(') => {
+                let end = group_end(input, index)?;
+                expand_into(
+                    &input[index + 1..end],
+                    &format!("{}{}", variants, class),
+                    classes,
+                )?;
+
+                index = end + 1;
+                if bytes
+                    .get(index)
+                    .is_some_and(|byte| !byte.is_ascii_whitespace())
+                {
+                    return Err(format!(
+                        "Expected a space after the variant group `{}`",
+                        &input[start..index]
+                    ));
+                }
+            }
+            Some(b')') => return Err(format!("Unexpected `)` in `{}`", input)),
+            _ => classes.push(format!("{}{}", variants, class)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Expands variant groups: `md:hover:(bg-red-500 text-white)` becomes
+/// `md:hover:bg-red-500 md:hover:text-white`. Groups can be nested.
+pub fn expand_variant_groups(input: &str) -> Result<Cow<'_, str>, String> {
+    if !input.contains(['(', ')']) {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut classes: Vec<String> = Vec::new();
+    expand_into(input, "", &mut classes)?;
+
+    Ok(Cow::Owned(classes.join(" ")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expand_nested_groups() {
+        assert_eq!(
+            expand_variant_groups("flex hover:(bg-red-500 text-white) md:(p-2 focus:(ring !m-1))")
+                .unwrap(),
+            "flex hover:bg-red-500 hover:text-white md:p-2 md:focus:ring md:focus:!m-1"
+        );
+        assert_eq!(
+            expand_variant_groups("w-[calc(100%-1rem)] [&:nth-child(3)]:(p-1)").unwrap(),
+            "w-[calc(100%-1rem)] [&:nth-child(3)]:p-1"
+        );
+    }
+
+    #[test]
+    fn reject_malformed_groups() {
+        assert!(expand_variant_groups("hover:(a b").is_err());
+        assert!(expand_variant_groups("hover:a)").is_err());
+        assert!(expand_variant_groups("hover:(a)b").is_err());
+        assert!(expand_variant_groups("bg-(--x))").is_err());
+    }
+
+    #[test]
+    fn keep_css_variable_values() {
+        assert_eq!(
+            expand_variant_groups("bg-(--x) p-1").unwrap(),
+            "bg-(--x) p-1"
+        );
+        assert_eq!(
+            expand_variant_groups("md:(bg-(--brand) hover:fill-(--icon))").unwrap(),
+            "md:bg-(--brand) md:hover:fill-(--icon)"
+        );
+    }
+}
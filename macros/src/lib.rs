@@ -1,9 +1,54 @@
-use macros_core::{cn_impl, variant_impl, Error};
+use macros_core::{
+    cn_impl, cn_list_impl, cn_static_impl, cn_str_impl, css_impl, css_module_impl,
+    into_expr_compile_error, variant_impl, variants_impl, Error,
+};
 use proc_macro::TokenStream;
 
 #[proc_macro]
 pub fn cn(input: TokenStream) -> TokenStream {
     cn_impl(input)
+        .unwrap_or_else(into_expr_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn cn_list(input: TokenStream) -> TokenStream {
+    cn_list_impl(input)
+        .unwrap_or_else(into_expr_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn cn_static(input: TokenStream) -> TokenStream {
+    cn_static_impl(input)
+        .unwrap_or_else(into_expr_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn cn_str(input: TokenStream) -> TokenStream {
+    cn_str_impl(input)
+        .unwrap_or_else(into_expr_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn css(input: TokenStream) -> TokenStream {
+    css_impl(input)
+        .unwrap_or_else(into_expr_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn css_module(input: TokenStream) -> TokenStream {
+    css_module_impl(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn variants(input: TokenStream) -> TokenStream {
+    variants_impl(input)
         .unwrap_or_else(Error::into_compile_error)
         .into()
 }
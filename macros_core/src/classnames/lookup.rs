@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use classlist::cleanup_cnl_with;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    config::Config,
+    utils::{append_separated_coma, global_deps},
+};
+
+use super::parsers::{CnIdentTupple, CnItem};
+
+/// Upper bound of conditional tuples expanded into a lookup table (2^N match arms).
+pub const MAX_LOOKUP_CONDITIONS: usize = 5;
+
+fn collect_conditions(items: &[CnItem]) -> Option<Vec<&CnIdentTupple>> {
+    let mut conditions: Vec<&CnIdentTupple> = Vec::new();
+
+    for item in items {
+        match item {
+            CnItem::Str(_) => {}
+            CnItem::Tuple(tuple) => conditions.push(tuple),
+            CnItem::Ident(_) | CnItem::Map(_) => return None,
+        }
+    }
+
+    if conditions.is_empty() || conditions.len() > MAX_LOOKUP_CONDITIONS {
+        return None;
+    }
+
+    Some(conditions)
+}
+
+fn resolve_classes(items: &[CnItem], state: &[bool], config: &Config) -> String {
+    let mut line = String::new();
+    let mut conditions = state.iter();
+
+    for item in items {
+        let part = match item {
+            CnItem::Str(string) => Some(string.as_str()),
+            CnItem::Tuple(tuple) => match conditions.next() {
+                Some(true) => Some(tuple.if_cond.as_str()),
+                _ => tuple.else_cond.as_deref(),
+            },
+            CnItem::Ident(_) | CnItem::Map(_) => None,
+        };
+
+        if let Some(part) = part {
+            line.push(' ');
+            line.push_str(part);
+        }
+    }
+
+    config.sort_classes(&cleanup_cnl_with(&line, config.merge_strategy()))
+}
+
+/// Builds a `match` over every combination of the conditional tuples, each arm
+/// returning the already deduplicated `&'static str`. Returns `None` when the
+/// items contain anything besides literals and conditional tuples.
+pub fn lookup_tokens(items: &[CnItem], config: &Config) -> Option<TokenStream> {
+    let conditions = collect_conditions(items)?;
+    let global_dep = global_deps();
+
+    let scrutinee = conditions
+        .iter()
+        .map(|tuple| {
+            let exp = &tuple.exp;
+            quote! { #global_dep Condition::is_active(&(#exp)) }
+        })
+        .collect::<Vec<TokenStream>>();
+
+    let mut arms: BTreeMap<String, Vec<TokenStream>> = BTreeMap::new();
+    for combination in 0..(1usize << conditions.len()) {
+        let state = (0..conditions.len())
+            .map(|index| combination & (1 << index) != 0)
+            .collect::<Vec<bool>>();
+
+        let pattern = state
+            .iter()
+            .map(|value| quote! { #value })
+            .collect::<Vec<TokenStream>>();
+        let mut pattern_tokens = TokenStream::new();
+        append_separated_coma(&mut pattern_tokens, &pattern);
+
+        arms.entry(resolve_classes(items, &state, config))
+            .or_default()
+            .push(quote! { (#pattern_tokens,) });
+    }
+
+    let arms = arms
+        .into_iter()
+        .map(|(classes, patterns)| quote! { #(#patterns)|* => #classes })
+        .collect::<Vec<TokenStream>>();
+
+    let mut scrutinee_tokens = TokenStream::new();
+    append_separated_coma(&mut scrutinee_tokens, &scrutinee);
+
+    let mut arms_tokens = TokenStream::new();
+    append_separated_coma(&mut arms_tokens, &arms);
+
+    Some(quote! {
+        match (#scrutinee_tokens,) {
+            #arms_tokens
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::classnames::parsers::CnParser;
+    use crate::config::{MergeConfig, OrderConfig};
+    use syn::parse_str;
+
+    fn items(input: &str) -> Vec<CnItem> {
+        parse_str::<CnParser>(input)
+            .unwrap()
+            .into_cn_tokens()
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn resolves_every_combination() {
+        let items = items(r#""a b", (first, "c a", "d"), "b", (second, "e")"#);
+
+        let last = Config::default();
+        let first = Config {
+            merge: MergeConfig::First,
+            ..Config::default()
+        };
+
+        assert_eq!(resolve_classes(&items, &[true, true], &last), "c a b e");
+        assert_eq!(resolve_classes(&items, &[false, true], &last), "a d b e");
+        assert_eq!(resolve_classes(&items, &[false, false], &last), "a d b");
+        assert_eq!(resolve_classes(&items, &[true, true], &first), "a c b e");
+        assert!(lookup_tokens(&items, &last).is_some());
+    }
+
+    #[test]
+    fn skips_dynamic_items() {
+        let last = Config::default();
+
+        assert!(lookup_tokens(&items(r#""a", size, (first, "c")"#), &last).is_none());
+        assert!(lookup_tokens(&items(r#""a", "b""#), &last).is_none());
+    }
+
+    #[test]
+    fn sorts_each_combination() {
+        let items = items(r#""p-4 flex", (first, "hover:underline mt-2")"#);
+        let tailwind = Config {
+            order: OrderConfig::Tailwind,
+            ..Config::default()
+        };
+
+        assert_eq!(
+            resolve_classes(&items, &[true], &tailwind),
+            "mt-2 flex p-4 hover:underline"
+        );
+    }
+}
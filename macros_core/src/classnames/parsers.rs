@@ -1,15 +1,22 @@
+use crate::config::{config, Config};
+use crate::manifest::record_classes;
+use crate::stylesheet::validate_classes;
 use crate::utils::global_deps;
 use crate::utils::ident_by_num;
 
-use super::units::{CnIdent, CnTupleExp, CnUnit};
-use classlist::cleanup_cnl;
+use super::lookup::lookup_tokens;
+use super::units::{
+    parse_variants, CnFormat, CnFormatArg, CnIdent, CnMap, CnPrefixedValue, CnTupleExp, CnUnit,
+};
+use classlist::{expand_variant_groups, prefix_classes, MergeStrategy};
 use proc_macro2::{Span, TokenStream};
+use std::collections::HashSet;
 use quote::quote;
 use quote::ToTokens;
 use quote::TokenStreamExt;
 use syn::{
     parse::{Parse, ParseStream},
-    Error, LitInt, LitStr, Result,
+    Error, LitInt, LitStr, Result, Token, Type,
 };
 
 
@@ -26,15 +33,26 @@ impl Parse for CnParser {
                 let _ = input.parse::<syn::token::Comma>()?;
             }
 
-            if input.peek(LitStr) {
-                items.push(CnUnit::Str(input.parse()?));
+            let variants = parse_variants(input)?;
+            let unit = if input.peek(LitStr) {
+                CnUnit::Str(input.parse()?)
+            } else if CnMap::peek(input) {
+                let field = CnFormatArg::Index(input.parse()?);
+                CnUnit::Map(CnMap::parse_with_field(input, field)?)
             } else if input.peek(LitInt) {
-                items.push(CnUnit::Int(input.parse()?));
+                CnUnit::Int(input.parse()?)
+            } else if CnFormat::peek(input) {
+                CnUnit::Format(input.parse()?)
             } else if CnIdent::peek(input) {
-                items.push(CnUnit::Ident(input.parse::<CnIdent>()?))
+                let ident = input.parse::<CnIdent>()?;
+                match input.peek(Token![=>]) {
+                    true => CnUnit::Map(CnMap::parse_with_field(input, CnFormatArg::Ident(ident))?),
+                    false => CnUnit::Ident(ident),
+                }
             } else {
-                items.push(CnUnit::Tuple(input.parse()?))
-            }
+                CnUnit::Tuple(input.parse()?)
+            };
+            items.push(unit.with_variants(variants));
 
             expect_comma = true;
         }
@@ -45,10 +63,31 @@ impl Parse for CnParser {
 
 pub trait CheckVariantIndexes {
     fn check_index(&mut self, value: u8, span: Span) -> Result<()>;
+
+    /// Type of the field, to validate value maps against.
+    fn index_type(&self, _value: u8) -> Option<Type> {
+        None
+    }
 }
 
 pub trait CheckVariantIdents {
     fn check_ident(&mut self, value: CnIdent) -> Result<()>;
+
+    /// Type of the field, to validate value maps against.
+    fn ident_type(&self, _value: &CnIdent) -> Option<Type> {
+        None
+    }
+
+    /// Whether a field is named `name`, ignoring case.
+    fn has_field(&self, _name: &str) -> bool {
+        false
+    }
+}
+
+/// A `SCREAMING_CASE` constant that is not a field with the wrong case (`WIDTH` for
+/// `width`); write a path (`self::WIDTH`) to use a constant named like a field.
+fn is_constant<T: CheckVariantIdents>(ident: &CnIdent, checker: &T) -> bool {
+    ident.is_constant() && (ident.is_path() || !checker.has_field(&ident.ident.to_string()))
 }
 
 fn modify_int_ident<T: CheckVariantIndexes + CheckVariantIdents>(
@@ -67,18 +106,53 @@ fn modify_int_ident<T: CheckVariantIndexes + CheckVariantIdents>(
 }
 
 impl CnParser {
+    /// Checks every field reference against `checker`. Units using a field are put under
+    /// `variants` (`md:`), so the classes of a nested enum can be state-prefixed.
     pub fn check_idents<T: CheckVariantIndexes + CheckVariantIdents>(
         mut self,
         checker: &mut T,
+        variants: &str,
     ) -> Result<Self> {
         let mut units: Vec<CnUnit> = Vec::new();
 
         for unit in self.0 {
+            let unit = match unit {
+                CnUnit::Str(literal) if CnFormat::has_placeholders(&literal) => {
+                    CnUnit::Format(CnFormat::try_from(&literal)?)
+                }
+                unit => unit,
+            };
+            let unit = match unit.references_field() {
+                true => unit.with_variants(variants.to_string()),
+                false => unit,
+            };
+
             match unit {
                 CnUnit::Str(_) => units.push(unit),
+                CnUnit::Format(mut format) => {
+                    let mut args: Vec<CnFormatArg> = Vec::new();
+                    for arg in format.args {
+                        args.push(match arg {
+                            CnFormatArg::Index(literal) => {
+                                CnFormatArg::Ident(modify_int_ident(literal, checker)?)
+                            }
+                            CnFormatArg::Ident(ident) if is_constant(&ident, checker) => {
+                                CnFormatArg::Ident(ident)
+                            }
+                            CnFormatArg::Ident(ident) => {
+                                checker.check_ident(ident.clone())?;
+                                CnFormatArg::Ident(ident)
+                            }
+                        });
+                    }
+
+                    format.args = args;
+                    units.push(CnUnit::Format(format));
+                }
                 CnUnit::Int(literal) => {
                     units.push(CnUnit::Ident(modify_int_ident(literal, checker)?));
                 }
+                CnUnit::Ident(ref ident) if is_constant(ident, checker) => units.push(unit),
                 CnUnit::Ident(ref ident) => {
                     checker.check_ident(ident.clone())?;
                     units.push(unit);
@@ -98,6 +172,40 @@ impl CnParser {
                     tuple.exp = exp;
                     units.push(CnUnit::Tuple(tuple));
                 }
+                CnUnit::Prefixed(mut prefixed) => {
+                    let ident = match prefixed.value {
+                        CnPrefixedValue::Int(literal) => modify_int_ident(literal, checker)?,
+                        CnPrefixedValue::Ident(ident) if is_constant(&ident, checker) => ident,
+                        CnPrefixedValue::Ident(ident) => {
+                            checker.check_ident(ident.clone())?;
+                            ident
+                        }
+                    };
+
+                    prefixed.value = CnPrefixedValue::Ident(ident);
+                    units.push(CnUnit::Prefixed(prefixed));
+                }
+                CnUnit::Map(mut map) => {
+                    let (ident, ty) = match map.field {
+                        CnFormatArg::Index(literal) => {
+                            let index: u8 = literal.base10_parse()?;
+                            let ident = modify_int_ident(literal, checker)?;
+                            (ident, checker.index_type(index))
+                        }
+                        CnFormatArg::Ident(ident) if is_constant(&ident, checker) => (ident, None),
+                        CnFormatArg::Ident(ident) => {
+                            checker.check_ident(ident.clone())?;
+                            let ty = checker.ident_type(&ident);
+                            (ident, ty)
+                        }
+                    };
+                    map.field = CnFormatArg::Ident(ident);
+                    if let Some(ty) = ty {
+                        map.check_keys(&ty)?;
+                    }
+
+                    units.push(CnUnit::Map(map));
+                }
             }
         }
 
@@ -105,11 +213,157 @@ impl CnParser {
         Ok(self)
     }
 
-    pub fn to_cn_tokens(self) -> Result<CnTokens> {
-        Ok(CnTokens::try_from(self)?)
+    /// Expands variant groups (`hover:(bg-red-500 text-white)`) of every literal.
+    fn expand_groups(self) -> Result<Self> {
+        let expand = |classes: &str, span: Span| {
+            expand_variant_groups(classes)
+                .map(|classes| classes.into_owned())
+                .map_err(|message| Error::new(span, message))
+        };
+
+        let mut units: Vec<CnUnit> = Vec::new();
+        for unit in self.0 {
+            units.push(match unit {
+                CnUnit::Str(literal) => CnUnit::Str(LitStr::new(
+                    &expand(&literal.value(), literal.span())?,
+                    literal.span(),
+                )),
+                CnUnit::Tuple(mut tuple) => {
+                    tuple.if_cond = expand(&tuple.if_cond, tuple.span)?;
+                    tuple.else_cond = tuple
+                        .else_cond
+                        .map(|else_cond| expand(&else_cond, tuple.span))
+                        .transpose()?;
+                    CnUnit::Tuple(tuple)
+                }
+                CnUnit::Map(mut map) => {
+                    for arm in &mut map.arms {
+                        arm.classes = expand(&arm.classes, map.span)?;
+                    }
+                    CnUnit::Map(map)
+                }
+                unit => unit,
+            });
+        }
+
+        Ok(Self(units))
+    }
+
+    /// Expands aliases and applies the prefix from `cln.toml` to every literal class.
+    fn apply_config(self, config: &Config) -> Self {
+        let units = self
+            .0
+            .into_iter()
+            .map(|unit| match unit {
+                CnUnit::Str(literal) => CnUnit::Str(LitStr::new(
+                    &config.transform_classes(&literal.value()),
+                    literal.span(),
+                )),
+                CnUnit::Tuple(mut tuple) => {
+                    tuple.if_cond = config.transform_classes(&tuple.if_cond);
+                    tuple.else_cond = tuple
+                        .else_cond
+                        .map(|else_cond| config.transform_classes(&else_cond));
+                    CnUnit::Tuple(tuple)
+                }
+                CnUnit::Map(mut map) => {
+                    for arm in &mut map.arms {
+                        arm.classes = config.transform_classes(&arm.classes);
+                    }
+                    CnUnit::Map(map)
+                }
+                unit => unit,
+            })
+            .collect();
+
+        Self(units)
+    }
+
+    /// Literal classes of every unit with the span to report them at.
+    pub fn literal_classes(&self) -> Vec<(String, Span)> {
+        self.0
+            .iter()
+            .filter_map(|unit| match unit {
+                CnUnit::Str(literal) => Some((literal.value(), literal.span())),
+                CnUnit::Tuple(tuple) => {
+                    let else_cond = tuple.else_cond.as_deref().unwrap_or_default();
+                    Some((format!("{} {}", tuple.if_cond, else_cond), tuple.span))
+                }
+                CnUnit::Map(map) => Some((map.classes(), map.span)),
+                CnUnit::Int(_) | CnUnit::Ident(_) | CnUnit::Prefixed(_) | CnUnit::Format(_) => None,
+            })
+            .collect()
+    }
+
+    /// Checks literal classes against the configured stylesheet, if any.
+    pub fn validate_classes(&self) -> Result<()> {
+        let mut errors: Option<Error> = None;
+
+        for (classes, span) in self.literal_classes() {
+            if let Err(error) = validate_classes(&classes, span) {
+                match errors.as_mut() {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+
+        errors.map_or(Ok(()), Err)
+    }
+
+    /// Writes literal classes to the class manifest, if it is enabled.
+    pub fn record_classes(&self) -> Result<()> {
+        for (classes, span) in self.literal_classes() {
+            record_classes(&classes, span)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_cn_tokens(self) -> Result<CnTokens> {
+        CnTokens::try_from(self)
+    }
+
+    pub fn into_static_tokens(self) -> Result<CnStaticTokens> {
+        for unit in &self.0 {
+            let error = match unit {
+                CnUnit::Str(_) | CnUnit::Int(_) => continue,
+                CnUnit::Ident(ident) => Error::new_spanned(ident, STATIC_IDENT_ERROR),
+                CnUnit::Tuple(tuple) => match tuple.exp {
+                    CnTupleExp::Bool(_) => continue,
+                    _ => Error::new(tuple.span, STATIC_TUPLE_ERROR),
+                },
+                CnUnit::Prefixed(prefixed) => match &prefixed.value {
+                    CnPrefixedValue::Int(_) => continue,
+                    CnPrefixedValue::Ident(ident) => Error::new_spanned(ident, STATIC_IDENT_ERROR),
+                },
+                CnUnit::Format(format) => Error::new(format.span, STATIC_IDENT_ERROR),
+                CnUnit::Map(map) => Error::new(map.span, STATIC_IDENT_ERROR),
+            };
+
+            return Err(error);
+        }
+
+        let classes = self
+            .into_cn_tokens()?
+            .0
+            .into_iter()
+            .filter_map(|item| match item {
+                CnItem::Str(string) => Some(string),
+                _ => None,
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        Ok(CnStaticTokens(classes))
     }
 }
 
+const STATIC_IDENT_ERROR: &str =
+    "Only literal classes are allowed in a static class list. This value is only known at runtime";
+const STATIC_TUPLE_ERROR: &str =
+    "Only `true`/`false` conditions are allowed in a static class list";
+
 #[derive(Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct CnIdentTupple {
@@ -139,33 +393,81 @@ pub enum CnItem {
     Str(String),
     Ident(TokenStream),
     Tuple(CnIdentTupple),
+    Map(CnMap),
 }
 
 
+/// Capacity reserved for each dynamic unit whose length is unknown at compile time.
+const DYNAMIC_CAPACITY_HINT: usize = 16;
+
 #[cfg_attr(feature = "debug", derive(Debug))]
-pub struct CnTokens(Vec<CnItem>);
+pub struct CnTokens(pub(crate) Vec<CnItem>);
 
 fn merge_string(value: String, prefix_string: &mut String, items: &mut Vec<CnItem>) {
     let prepared_string = value.trim();
     if prepared_string.is_empty() {
         return;
     }
-    if items.len() == 0 {
+    if items.is_empty() {
         prefix_string.push_str(&format!(" {}", prepared_string));
         return;
     }
 
-    let last_item = items.last_mut();
-    if let Some(item) = last_item {
-        if let CnItem::Str(last_string) = item {
-            last_string.push_str(&format!(" {}", prepared_string));
-            return;
-        }
+    if let Some(CnItem::Str(last_string)) = items.last_mut() {
+        last_string.push_str(&format!(" {}", prepared_string));
+        return;
     }
 
     items.push(CnItem::Str(prepared_string.to_string()));
 }
 
+/// Removes every literal class repeated in another literal segment (keeping the occurrence
+/// the merge strategy selects), so the runtime only has to compare dynamic contributions
+/// against the static ones.
+fn dedup_static_items(items: &mut Vec<CnItem>, strategy: MergeStrategy) {
+    let mut seen: HashSet<String> = HashSet::new();
+    let reversed = strategy == MergeStrategy::Last;
+
+    let mut strings = items
+        .iter_mut()
+        .filter_map(|item| match item {
+            CnItem::Str(string) => Some(string),
+            _ => None,
+        })
+        .collect::<Vec<&mut String>>();
+    if reversed {
+        strings.reverse();
+    }
+
+    for string in strings {
+        let mut classes = string
+            .split(' ')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>();
+        if reversed {
+            classes.reverse();
+        }
+        classes.retain(|class| seen.insert(class.to_string()));
+        if reversed {
+            classes.reverse();
+        }
+
+        *string = classes.join(" ");
+    }
+
+    items.retain(|item| !matches!(item, CnItem::Str(string) if string.is_empty()));
+}
+
+/// Sorts every literal segment, so single-string lists need no sorting at runtime.
+fn sort_static_items(items: &mut [CnItem], config: &Config) {
+    for item in items {
+        if let CnItem::Str(string) = item {
+            *string = config.sort_classes(string);
+        }
+    }
+}
+
 impl TryFrom<CnParser> for CnTokens {
     type Error = Error;
 
@@ -173,6 +475,11 @@ impl TryFrom<CnParser> for CnTokens {
         let mut items: Vec<CnItem> = Vec::new();
         let mut prefix_string = String::new();
 
+        let config = config()?;
+        let value = value.expand_groups()?.apply_config(config);
+        value.validate_classes()?;
+        value.record_classes()?;
+
         for unit in value.0 {
             match unit {
                 CnUnit::Str(literal) => {
@@ -182,6 +489,47 @@ impl TryFrom<CnParser> for CnTokens {
                     merge_string(literal.to_string(), &mut prefix_string, &mut items)
                 }
                 CnUnit::Ident(ident) => items.push(CnItem::Ident(ident.stream)),
+                CnUnit::Format(format) => {
+                    if format
+                        .args
+                        .iter()
+                        .any(|arg| matches!(arg, CnFormatArg::Index(_)))
+                    {
+                        return Err(Error::new(
+                            format.span,
+                            "Positional placeholders are only supported in #[class]. Name the value: `{width}`",
+                        ));
+                    }
+
+                    items.push(CnItem::Ident(format.to_token_stream()))
+                }
+                CnUnit::Map(map) => {
+                    if let CnFormatArg::Index(_) = map.field {
+                        return Err(Error::new(
+                            map.span,
+                            "Positional fields are only supported in #[class]. Map a named value: `size => { ... }`",
+                        ));
+                    }
+
+                    items.push(CnItem::Map(map))
+                }
+                CnUnit::Prefixed(prefixed) => {
+                    let variants = prefixed.variants;
+                    match prefixed.value {
+                        CnPrefixedValue::Int(literal) => merge_string(
+                            prefix_classes(&literal.to_string(), &variants),
+                            &mut prefix_string,
+                            &mut items,
+                        ),
+                        CnPrefixedValue::Ident(ident) => {
+                            let global_dep = global_deps();
+                            let stream = ident.stream;
+                            items.push(CnItem::Ident(
+                                quote! { #global_dep Prefixed::new(#variants, #stream) },
+                            ))
+                        }
+                    }
+                }
                 CnUnit::Tuple(tuple) => match tuple.exp {
                     CnTupleExp::Ident(ident) => items.push(CnItem::Tuple(CnIdentTupple {
                         exp: ident.stream,
@@ -210,47 +558,376 @@ impl TryFrom<CnParser> for CnTokens {
 
         let trimmed_prefix_string = prefix_string.trim();
         if !trimmed_prefix_string.is_empty() {
-            items.push(CnItem::Str(cleanup_cnl(trimmed_prefix_string)));
+            items.push(CnItem::Str(trimmed_prefix_string.to_string()));
             items.rotate_right(1);
         }
 
+        dedup_static_items(&mut items, config.merge_strategy());
+        sort_static_items(&mut items, config);
+
         Ok(Self(items))
     }
 }
 
-impl ToTokens for CnTokens {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        if self.0.len() == 0 {
-            tokens.append_all(quote! {String::new()});
-            return;
-        }
-
-        if self.0.len() == 1 {
-            if let Some(first) = self.0.first() {
-                if let CnItem::Str(first_string) = first {
-                    tokens.append_all(quote! { #first_string .to_string() });
-                    return;
+impl CnTokens {
+    fn capacity_hint(&self) -> usize {
+        self.0
+            .iter()
+            .map(|item| match item {
+                CnItem::Str(string) => string.len() + 1,
+                CnItem::Tuple(tuple) => {
+                    let else_len = tuple.else_cond.as_ref().map_or(0, String::len);
+                    tuple.if_cond.len().max(else_len) + 1
                 }
-            }
-        }
+                CnItem::Ident(_) => DYNAMIC_CAPACITY_HINT,
+                CnItem::Map(map) => map.max_len() + 1,
+            })
+            .sum()
+    }
 
+    fn builder_tokens(&self) -> TokenStream {
         let global_dep = global_deps();
-        tokens.append_all(quote! { #global_dep CnBuilder::new() });
+        let capacity = self.capacity_hint();
+        let strategy = config().map(Config::strategy_tokens).unwrap_or_default();
+        let order = config().map(Config::order_tokens).unwrap_or_default();
+        let prefix = config().map(Config::prefix_tokens).unwrap_or_default();
+        let mut tokens = quote! {
+            #global_dep CnBuilder::with_capacity(#capacity) #strategy #order #prefix
+        };
 
         let stream_list = self
             .0
             .clone()
             .into_iter()
             .map(|item| match item {
-                CnItem::Str(string) => quote! {#string},
-                CnItem::Ident(ident) => quote! {#ident},
-                CnItem::Tuple(tuple) => quote! {#tuple},
+                CnItem::Str(string) => quote! {.add_static(#string)},
+                CnItem::Ident(ident) => quote! {.add(#ident)},
+                // Tuple classes are prefixed at compile time already.
+                CnItem::Tuple(tuple) => {
+                    let CnIdentTupple {
+                        exp,
+                        if_cond,
+                        else_cond,
+                    } = tuple;
+                    let else_cond = else_cond.unwrap_or_default();
+                    quote! {
+                        .add_prepared(match #global_dep Condition::is_active(&(#exp)) {
+                            true => #if_cond,
+                            false => #else_cond,
+                        })
+                    }
+                }
+                // So are the classes of every arm of a value map.
+                CnItem::Map(map) => quote! {.add_prepared(#map)},
             })
-            .map(|ts| quote! {.add(#ts)})
             .collect::<Vec<TokenStream>>();
 
         tokens.append_all(stream_list);
+        tokens
+    }
+
+    /// Every literal class the list can produce, in order: conditional tuples contribute
+    /// both branches, value maps every arm.
+    pub fn static_classes(&self) -> Vec<&str> {
+        let mut classes: Vec<&str> = Vec::new();
+
+        for item in &self.0 {
+            match item {
+                CnItem::Str(string) => classes.push(string),
+                CnItem::Tuple(tuple) => {
+                    classes.push(&tuple.if_cond);
+                    classes.extend(tuple.else_cond.as_deref());
+                }
+                CnItem::Map(map) => classes.extend(map.arms.iter().map(|arm| arm.classes.as_str())),
+                CnItem::Ident(_) => {}
+            }
+        }
+
+        classes
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .collect()
+    }
+
+    pub(crate) fn single_string(&self) -> Option<&String> {
+        match self.0.as_slice() {
+            [CnItem::Str(first_string)] => Some(first_string),
+            _ => None,
+        }
+    }
+
+    fn lookup(&self) -> Option<TokenStream> {
+        let default = Config::default();
+        lookup_tokens(&self.0, config().unwrap_or(&default))
+    }
+
+    /// `&'static str` expansion for `cn_str!`: the literal classes, or a precomputed
+    /// lookup. `None` when the list needs a builder at runtime.
+    pub fn str_tokens(&self) -> Option<TokenStream> {
+        if self.0.is_empty() {
+            return Some(quote! { "" });
+        }
+
+        if let Some(first_string) = self.single_string() {
+            return Some(quote! { #first_string });
+        }
+
+        self.lookup()
+    }
+}
+
+impl ToTokens for CnTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.0.is_empty() {
+            tokens.append_all(quote! {String::new()});
+            return;
+        }
+
+        if let Some(first_string) = self.single_string() {
+            tokens.append_all(quote! { #first_string .to_string() });
+            return;
+        }
+
+        if let Some(lookup) = self.lookup() {
+            tokens.append_all(quote! { (#lookup).to_string() });
+            return;
+        }
 
+        tokens.append_all(self.builder_tokens());
         tokens.append_all(quote! {.to_classlist()})
     }
 }
+
+/// Fully literal class list expanded into a `&'static str` literal.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnStaticTokens(String);
+
+impl ToTokens for CnStaticTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let classes = &self.0;
+        tokens.append_all(quote! { #classes });
+    }
+}
+
+/// Same units as [`CnTokens`], expanded into a `ClassList` instead of a `String`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnListTokens(pub CnTokens);
+
+impl ToTokens for CnListTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let global_dep = global_deps();
+
+        if self.0 .0.is_empty() {
+            tokens.append_all(quote! { #global_dep ClassList::new() });
+            return;
+        }
+
+        if let Some(first_string) = self.0.single_string() {
+            tokens.append_all(quote! { #global_dep ClassList::from(#first_string) });
+            return;
+        }
+
+        tokens.append_all(self.0.builder_tokens());
+        tokens.append_all(quote! {.to_list()})
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn static_items(input: &str) -> Vec<String> {
+        parse_str::<CnParser>(input)
+            .unwrap()
+            .into_cn_tokens()
+            .unwrap()
+            .0
+            .into_iter()
+            .filter_map(|item| match item {
+                CnItem::Str(string) => Some(string),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn static_segments_are_deduplicated() {
+        assert_eq!(
+            static_items(r#""a b a", size, "c b", color, "a""#),
+            vec!["c b", "a"]
+        );
+        assert_eq!(static_items(r#""a", size, "a""#), vec!["a"]);
+    }
+
+    #[test]
+    fn variant_prefixes() {
+        assert_eq!(
+            static_items(r#""a", md: hover: "b c", lg: (true, "d"), sm: 4"#),
+            vec!["a md:hover:b md:hover:c lg:d sm:4"]
+        );
+        assert_eq!(
+            static_items(r#"group-hover: peer-focus-visible: "a", "2xl": (true, "b"), "c""#),
+            vec!["group-hover:peer-focus-visible:a 2xl:b c"]
+        );
+
+        let tokens = parse_str::<CnParser>("md: size")
+            .unwrap()
+            .into_cn_tokens()
+            .unwrap();
+        assert!(matches!(
+            tokens.0.as_slice(),
+            [CnItem::Ident(stream)] if stream.to_string().contains("Prefixed :: new (\"md:\" , size)")
+        ));
+    }
+
+    #[test]
+    fn variant_groups_are_expanded() {
+        assert_eq!(
+            static_items(r#""hover:(a b)", (true, "md:(c focus:(d))")"#),
+            vec!["hover:a hover:b md:c md:focus:d"]
+        );
+        assert!(parse_str::<CnParser>(r#""hover:(a""#)
+            .unwrap()
+            .into_cn_tokens()
+            .is_err());
+    }
+
+    #[test]
+    fn format_literals() {
+        let input = r#""grid", md: f "col-span-{n:02}", f "{{w}}-{self.w}""#;
+        let tokens = parse_str::<CnParser>(input)
+            .unwrap()
+            .into_cn_tokens()
+            .unwrap();
+        let streams = tokens
+            .0
+            .iter()
+            .filter_map(|item| match item {
+                CnItem::Ident(stream) => Some(stream.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            streams,
+            [
+                r#"format ! ("md:(col-span-{:02})" , n)"#,
+                r#"format ! ("{{w}}-{}" , self . w)"#
+            ]
+        );
+        assert!(parse_str::<CnParser>(r#"f "col-{0}""#)
+            .unwrap()
+            .into_cn_tokens()
+            .is_err());
+    }
+
+    #[test]
+    fn value_maps() {
+        let map = |input: &str| match parse_str::<CnParser>(input).unwrap().0.remove(0) {
+            CnUnit::Map(map) => map,
+            _ => unreachable!(),
+        };
+        let ty = parse_str::<Type>("u8").unwrap();
+
+        assert!(map(r#"size => { 1: "p-1", 2 | 3: "p-2", _: "p-4" }"#)
+            .check_keys(&ty)
+            .is_ok());
+        assert!(map(r#"size => { 300: "p-1", _: "p-4" }"#)
+            .check_keys(&ty)
+            .is_err());
+        assert!(map(r#"size => { "1": "p-1", _: "p-4" }"#)
+            .check_keys(&ty)
+            .is_err());
+        assert!(map(r#"size => { 1: "p-1" }"#).check_keys(&ty).is_err());
+        let ty = parse_str::<Type>("u128").unwrap();
+        assert!(
+            map(r#"size => { 340282366920938463463374607431768211455: "p-1", _: "p-4" }"#)
+                .check_keys(&ty)
+                .is_ok()
+        );
+        assert!(
+            map(r#"size => { 340282366920938463463374607431768211456: "p-1", _: "p-4" }"#)
+                .check_keys(&ty)
+                .is_err()
+        );
+        assert!(map(r#"on => { true: "a", false: "b" }"#)
+            .check_keys(&parse_str::<Type>("&bool").unwrap())
+            .is_ok());
+        assert!(parse_str::<CnParser>(r#"size => { _: "a", 1: "b" }"#).is_err());
+        assert!(parse_str::<CnParser>(r#"size => { 1: "a", 1: "b" }"#).is_err());
+
+        let tokens = parse_str::<CnParser>(r#""a", md: size => { 1: "p-1", _: "p-4" }"#)
+            .unwrap()
+            .into_cn_tokens()
+            .unwrap();
+        assert!(matches!(
+            tokens.0.as_slice(),
+            [CnItem::Str(_), CnItem::Map(map)] if map.classes() == "md:p-1 md:p-4"
+        ));
+    }
+
+    #[test]
+    fn static_classes() {
+        let tokens = parse_str::<CnParser>(
+            r#""a b", size, (active, "c", "d e"), md: width => { 1: "f", _: "g" }"#,
+        )
+        .unwrap()
+        .into_cn_tokens()
+        .unwrap();
+
+        assert_eq!(
+            tokens.static_classes(),
+            ["a", "b", "c", "d", "e", "md:f", "md:g"]
+        );
+    }
+
+    #[test]
+    fn lookup_is_a_string() {
+        let tokens = |input: &str| {
+            parse_str::<CnParser>(input)
+                .unwrap()
+                .into_cn_tokens()
+                .unwrap()
+        };
+        let lookup = tokens(r#""a", (active, "b", "c")"#);
+
+        assert!(lookup
+            .to_token_stream()
+            .to_string()
+            .ends_with(". to_string ()"));
+        assert!(lookup
+            .str_tokens()
+            .unwrap()
+            .to_string()
+            .starts_with("match"));
+        assert_eq!(
+            tokens(r#""a b""#).str_tokens().unwrap().to_string(),
+            r#""a b""#
+        );
+        assert!(tokens(r#""a", size"#).str_tokens().is_none());
+    }
+
+    #[test]
+    fn static_lists() {
+        let tokens = |input: &str| parse_str::<CnParser>(input).unwrap().into_static_tokens();
+        let error = |input: &str| match tokens(input) {
+            Ok(_) => panic!("`{}` should be rejected", input),
+            Err(error) => error.to_string(),
+        };
+
+        let Ok(list) = tokens(r#""a b", (true, "c", "d"), (false, "e"), "a""#) else {
+            panic!("literal classes should be accepted");
+        };
+        assert_eq!(list.to_token_stream().to_string(), r#""b c a""#);
+
+        assert_eq!(error(r#""a", size"#), STATIC_IDENT_ERROR);
+        assert_eq!(error(r#""a", (active, "b")"#), STATIC_TUPLE_ERROR);
+        assert_eq!(error(r#""a", md: size"#), STATIC_IDENT_ERROR);
+        assert_eq!(
+            error(r#""a", size => { 1: "b", _: "c" }"#),
+            STATIC_IDENT_ERROR
+        );
+        assert_eq!(error(r#""a", f "p-{size}""#), STATIC_IDENT_ERROR);
+    }
+}
@@ -1,10 +1,13 @@
 use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     parse2,
-    token::{And, AndAnd, Colon, Comma, Dot, Star},
-    Error, Ident, LitBool, LitInt, LitStr, Result,
+    token::{
+        And, AndAnd, Colon, Comma, Dot, FatArrow, Minus, Or, PathSep, SelfValue, Star, Underscore,
+    },
+    Error, Ident, Lit, LitBool, LitInt, LitStr, Result, Type,
 };
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -34,6 +37,7 @@ impl Parse for CnGroup {
 #[derive(Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct CnIdent {
+    #[allow(dead_code)]
     pub sym: String,
     pub stream: TokenStream,
     pub ident: Ident,
@@ -41,7 +45,29 @@ pub struct CnIdent {
 
 impl CnIdent {
     pub fn peek(input: ParseStream) -> bool {
-        input.peek(Ident) || input.peek(And) || input.peek(AndAnd) || input.peek(Star)
+        input.peek(Ident)
+            || input.peek(SelfValue)
+            || input.peek(And)
+            || input.peek(AndAnd)
+            || input.peek(Star)
+    }
+
+    /// Path before the first field access: `consts::BUTTON` of `consts::BUTTON.primary`.
+    fn path(&self) -> &str {
+        self.sym.split('.').next().unwrap_or_default()
+    }
+
+    /// Starts with a `SCREAMING_CASE` constant (`BUTTON.primary`, `self::WIDTH`), so it
+    /// does not refer to a variant parameter.
+    pub fn is_constant(&self) -> bool {
+        let name = self.path().rsplit("::").next().unwrap_or_default();
+        name.starts_with(|c: char| c.is_ascii_uppercase())
+            && !name.contains(|c: char| c.is_ascii_lowercase())
+    }
+
+    /// Written as a path: `self::WIDTH`, `consts::WIDTH`.
+    pub fn is_path(&self) -> bool {
+        self.path().contains("::")
     }
 }
 
@@ -68,7 +94,11 @@ impl Parse for CnIdent {
             stream.append(prefix);
         }
 
-        let first_ident: Ident = input.parse()?;
+        // `self.size` inside methods.
+        let first_ident: Ident = match input.peek(SelfValue) {
+            true => Ident::parse_any(input)?,
+            false => input.parse()?,
+        };
         sym.push_str(&first_ident.to_string());
         stream.append(first_ident.clone());
 
@@ -81,11 +111,11 @@ impl Parse for CnIdent {
                 stream.append(ident);
                 expect_ident = false;
             } else {
-                if input.peek(Comma) {
+                if input.peek(Comma) || input.peek(FatArrow) {
                     break;
                 } else if input.peek(Dot) {
                     let dot_literal: Dot = input.parse()?;
-                    sym.push_str(".");
+                    sym.push('.');
                     dot_literal.to_tokens(&mut stream);
                     expect_ident = true;
                 } else if  input.peek(Colon) {
@@ -125,16 +155,16 @@ pub enum CnTupleExp {
 impl Parse for CnTupleExp {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.peek(LitBool) {
-            return Ok(CnTupleExp::Bool(input.parse()?));
+            Ok(CnTupleExp::Bool(input.parse()?))
         } else if input.peek(LitInt) {
-            return Ok(CnTupleExp::Int(input.parse()?));
+            Ok(CnTupleExp::Int(input.parse()?))
         } else if CnIdent::peek(input) {
-            return Ok(CnTupleExp::Ident(input.parse()?));
+            Ok(CnTupleExp::Ident(input.parse()?))
         } else {
-            return Err(Error::new(
+            Err(Error::new(
                 input.span(),
                 "Incorrect expression token. Allowed only bool or variable (bool, Option<bool>",
-            ));
+            ))
         }
     }
 }
@@ -200,6 +230,431 @@ impl Parse for CnTuple {
     }
 }
 
+/// Value put under variant modifiers with `md: size`. Literals are rewritten into a
+/// variant group instead (`md: "p-4"` -> `"md:(p-4)"`).
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum CnPrefixedValue {
+    Int(LitInt),
+    Ident(CnIdent),
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnPrefixed {
+    pub variants: String,
+    pub value: CnPrefixedValue,
+}
+
+/// Field interpolated by a format literal or mapped by a value map: `0` (only inside
+/// `#[class]`) or `width`.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum CnFormatArg {
+    Index(LitInt),
+    Ident(CnIdent),
+}
+
+/// Format literal: `f "col-span-{n}"` in `cn!`, any literal with `{...}` in `#[class]`.
+/// `template` is a `format!` string with the placeholders emptied (`col-span-{}`).
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnFormat {
+    pub template: String,
+    pub args: Vec<CnFormatArg>,
+    pub span: Span,
+}
+
+impl CnFormat {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Ident)
+            && input.peek2(LitStr)
+            && input.fork().parse::<Ident>().is_ok_and(|f| f == "f")
+    }
+
+    pub fn has_placeholders(literal: &LitStr) -> bool {
+        literal.value().contains(['{', '}'])
+    }
+
+    fn parse_arg(arg: &str, span: Span) -> Result<CnFormatArg> {
+        if arg.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(CnFormatArg::Index(LitInt::new(arg, span)));
+        }
+
+        let error = || Error::new(span, format!("Invalid placeholder `{{{}}}`", arg));
+        let tokens = arg
+            .parse::<TokenStream>()
+            .map_err(|_| error())?
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(span);
+                token
+            })
+            .collect::<TokenStream>();
+
+        Ok(CnFormatArg::Ident(
+            parse2::<CnIdent>(tokens).map_err(|_| error())?,
+        ))
+    }
+}
+
+impl TryFrom<&LitStr> for CnFormat {
+    type Error = Error;
+
+    fn try_from(value: &LitStr) -> Result<Self> {
+        let span = value.span();
+        let literal = value.value();
+        let mut template = String::new();
+        let mut args: Vec<CnFormatArg> = Vec::new();
+        let mut chars = literal.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    template.push_str("{{");
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    template.push_str("}}");
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(Error::new(span, "Unclosed `{` in format literal")),
+                        }
+                    }
+
+                    let (arg, spec) = match placeholder.split_once(':') {
+                        Some((arg, spec)) => (arg.trim(), format!(":{}", spec)),
+                        None => (placeholder.trim(), String::new()),
+                    };
+                    if arg.is_empty() {
+                        return Err(Error::new(
+                            span,
+                            "Placeholders need a field: `{0}` or `{name}`",
+                        ));
+                    }
+
+                    args.push(Self::parse_arg(arg, span)?);
+                    template.push_str(&format!("{{{}}}", spec));
+                }
+                '}' => return Err(Error::new(span, "Unmatched `}` in format literal")),
+                c => template.push(c),
+            }
+        }
+
+        Ok(Self {
+            template,
+            args,
+            span,
+        })
+    }
+}
+
+impl Parse for CnFormat {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: Ident = input.parse()?;
+        let literal: LitStr = input.parse()?;
+
+        Self::try_from(&literal)
+    }
+}
+
+impl ToTokens for CnFormat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let template = &self.template;
+        let args = self.args.iter().map(|arg| match arg {
+            CnFormatArg::Index(index) => index.to_token_stream(),
+            CnFormatArg::Ident(ident) => ident.to_token_stream(),
+        });
+
+        tokens.append_all(quote! { format!(#template, #(#args),*) });
+    }
+}
+
+/// Key of a value map arm: a literal or `_`.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum CnMapKey {
+    Lit(Lit),
+    Wildcard(Underscore),
+}
+
+impl Parse for CnMapKey {
+    fn parse(input: ParseStream) -> Result<Self> {
+        match input.peek(Underscore) {
+            true => Ok(CnMapKey::Wildcard(input.parse()?)),
+            false => Ok(CnMapKey::Lit(input.parse()?)),
+        }
+    }
+}
+
+impl ToTokens for CnMapKey {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            CnMapKey::Lit(lit) => lit.to_tokens(tokens),
+            CnMapKey::Wildcard(underscore) => underscore.to_tokens(tokens),
+        }
+    }
+}
+
+impl CnMapKey {
+    /// Value compared to find repeated keys.
+    fn value(&self) -> String {
+        match self {
+            CnMapKey::Lit(Lit::Int(lit)) => lit.base10_digits().to_string(),
+            key => key.to_token_stream().to_string(),
+        }
+    }
+}
+
+/// `1 | 2: "p-1"` arm of a value map.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnMapArm {
+    pub keys: Vec<CnMapKey>,
+    pub classes: String,
+}
+
+impl Parse for CnMapArm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut keys: Vec<CnMapKey> = vec![input.parse()?];
+        while input.peek(Or) {
+            let _: Or = input.parse()?;
+            keys.push(input.parse()?);
+        }
+        let _: Colon = input.parse()?;
+
+        Ok(Self {
+            keys,
+            classes: input.parse::<LitStr>()?.value().trim().to_string(),
+        })
+    }
+}
+
+/// Classes the value of a field maps to: `0 => { 1: "p-1", 2: "p-2", _: "p-4" }`.
+/// Every class stays a literal, unlike a format literal.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CnMap {
+    pub field: CnFormatArg,
+    pub arms: Vec<CnMapArm>,
+    pub span: Span,
+}
+
+/// Kind of keys a field type accepts.
+enum CnMapKeyKind {
+    Int(i128, i128),
+    /// `u128`, whose upper half does not fit in `i128`.
+    U128,
+    Bool,
+    Char,
+    Str,
+}
+
+impl CnMapKeyKind {
+    fn of(ty: &Type) -> Option<Self> {
+        let name = match ty {
+            Type::Reference(reference) => return Self::of(&reference.elem),
+            Type::Group(group) => return Self::of(&group.elem),
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.segments.last()?.ident.to_string()
+            }
+            _ => return None,
+        };
+
+        Some(match name.as_str() {
+            "u8" => Self::Int(0, u8::MAX.into()),
+            "u16" => Self::Int(0, u16::MAX.into()),
+            "u32" => Self::Int(0, u32::MAX.into()),
+            "u64" | "usize" => Self::Int(0, u64::MAX.into()),
+            "u128" => Self::U128,
+            "i8" => Self::Int(i8::MIN.into(), i8::MAX.into()),
+            "i16" => Self::Int(i16::MIN.into(), i16::MAX.into()),
+            "i32" => Self::Int(i32::MIN.into(), i32::MAX.into()),
+            "i64" | "isize" => Self::Int(i64::MIN.into(), i64::MAX.into()),
+            "i128" => Self::Int(i128::MIN, i128::MAX),
+            "bool" => Self::Bool,
+            "char" => Self::Char,
+            "str" | "String" => Self::Str,
+            _ => return None,
+        })
+    }
+
+    /// Whether the integer `lit` is a value of the type.
+    fn contains(&self, lit: &LitInt) -> bool {
+        match self {
+            Self::Int(min, max) => lit
+                .base10_parse::<i128>()
+                .is_ok_and(|value| (*min..=*max).contains(&value)),
+            Self::U128 => lit.base10_parse::<u128>().is_ok(),
+            _ => false,
+        }
+    }
+}
+
+impl CnMap {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(LitInt) && input.peek2(FatArrow)
+    }
+
+    /// Parses the `=> { ... }` part following `field`.
+    pub fn parse_with_field(input: ParseStream, field: CnFormatArg) -> Result<Self> {
+        let _: FatArrow = input.parse()?;
+        let group: Group = input.parse()?;
+        if group.delimiter() != Delimiter::Brace {
+            return Err(Error::new_spanned(
+                group,
+                "Expected `{ value: \"classes\", ... }`",
+            ));
+        }
+
+        let arms = parse2::<CnMapArms>(group.stream())?.0;
+        let mut seen: Vec<String> = Vec::new();
+        for (index, arm) in arms.iter().enumerate() {
+            for key in &arm.keys {
+                if matches!(key, CnMapKey::Wildcard(_)) && index + 1 != arms.len() {
+                    return Err(Error::new_spanned(key, "The `_` arm must be the last one"));
+                }
+                if seen.contains(&key.value()) {
+                    return Err(Error::new_spanned(key, "Duplicate key"));
+                }
+                seen.push(key.value());
+            }
+        }
+        if arms.is_empty() {
+            return Err(Error::new(group.span(), "Expected at least one arm"));
+        }
+
+        Ok(Self {
+            field,
+            arms,
+            span: group.span(),
+        })
+    }
+
+    fn has_wildcard(&self) -> bool {
+        self.arms.iter().any(|arm| {
+            arm.keys
+                .iter()
+                .any(|key| matches!(key, CnMapKey::Wildcard(_)))
+        })
+    }
+
+    /// Checks every key against the type of the field. Unknown types are left to the
+    /// compiler.
+    pub fn check_keys(&self, ty: &Type) -> Result<()> {
+        let Some(kind) = CnMapKeyKind::of(ty) else {
+            return Ok(());
+        };
+        let ty_name = ty.to_token_stream().to_string();
+
+        let mut values: Vec<String> = Vec::new();
+        for key in self.arms.iter().flat_map(|arm| &arm.keys) {
+            let lit = match key {
+                CnMapKey::Lit(lit) => lit,
+                CnMapKey::Wildcard(_) => continue,
+            };
+            let valid = match (&kind, lit) {
+                (CnMapKeyKind::Int(..) | CnMapKeyKind::U128, Lit::Int(lit)) => {
+                    if !kind.contains(lit) {
+                        return Err(Error::new_spanned(
+                            lit,
+                            format!("Key is out of range for `{}`", ty_name),
+                        ));
+                    }
+                    true
+                }
+                (CnMapKeyKind::Bool, Lit::Bool(_))
+                | (CnMapKeyKind::Char, Lit::Char(_))
+                | (CnMapKeyKind::Str, Lit::Str(_)) => true,
+                _ => false,
+            };
+            if !valid {
+                return Err(Error::new_spanned(
+                    lit,
+                    format!("Key does not match the field type `{}`", ty_name),
+                ));
+            }
+            values.push(key.value());
+        }
+
+        let exhaustive = match kind {
+            CnMapKeyKind::Bool => {
+                values.iter().any(|v| v == "true") && values.iter().any(|v| v == "false")
+            }
+            _ => false,
+        };
+        if !exhaustive && !self.has_wildcard() {
+            return Err(Error::new(
+                self.span,
+                format!("Not every `{}` value has classes. Add a `_` arm", ty_name),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Classes of every arm.
+    pub fn classes(&self) -> String {
+        self.arms
+            .iter()
+            .map(|arm| arm.classes.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Longest class list of an arm.
+    pub fn max_len(&self) -> usize {
+        self.arms
+            .iter()
+            .map(|arm| arm.classes.len())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+struct CnMapArms(Vec<CnMapArm>);
+
+impl Parse for CnMapArms {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let arms = input.parse_terminated(CnMapArm::parse, Comma)?;
+
+        Ok(Self(arms.into_iter().collect()))
+    }
+}
+
+impl ToTokens for CnMap {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let field = match &self.field {
+            CnFormatArg::Index(index) => index.to_token_stream(),
+            CnFormatArg::Ident(ident) => ident.to_token_stream(),
+        };
+        let is_str = self
+            .arms
+            .iter()
+            .flat_map(|arm| &arm.keys)
+            .any(|key| matches!(key, CnMapKey::Lit(Lit::Str(_))));
+        let value = match is_str {
+            true => quote! { ::core::convert::AsRef::<str>::as_ref(&(#field)) },
+            false => field,
+        };
+        let arms = self.arms.iter().map(|CnMapArm { keys, classes }| {
+            quote! { #(#keys)|* => #classes }
+        });
+
+        tokens.append_all(quote! {
+            match #value {
+                #(#arms,)*
+            }
+        });
+    }
+}
+
 #[derive(Clone)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub enum CnUnit {
@@ -207,4 +662,115 @@ pub enum CnUnit {
     Int(LitInt),
     Ident(CnIdent),
     Tuple(CnTuple),
+    Prefixed(CnPrefixed),
+    Format(CnFormat),
+    Map(CnMap),
+}
+
+/// Parses the `md: hover:` modifiers in front of a unit, `md:hover:` for this example.
+/// Modifiers with dashes are written as is (`group-hover:`) or quoted (`"2xl":`).
+pub fn parse_variants(input: ParseStream) -> Result<String> {
+    let mut variants = String::new();
+
+    while peek_variant(input) {
+        let variant = parse_variant(input)?;
+        let _: Colon = input.parse()?;
+        variants.push_str(&format!("{}:", variant));
+    }
+
+    Ok(variants)
+}
+
+fn peek_variant(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    (fork.peek(LitStr) || fork.peek(Ident))
+        && parse_variant(&fork).is_ok()
+        && fork.peek(Colon)
+        && !fork.peek(PathSep)
+}
+
+/// One modifier, without its colon: `md`, `group-hover` or `"2xl"`.
+fn parse_variant(input: ParseStream) -> Result<String> {
+    if input.peek(LitStr) {
+        return Ok(input.parse::<LitStr>()?.value());
+    }
+
+    let mut variant = input.parse::<Ident>()?.to_string();
+    while input.peek(Minus) {
+        let _: Minus = input.parse()?;
+        variant.push('-');
+        variant.push_str(&Ident::parse_any(input)?.to_string());
+    }
+
+    Ok(variant)
+}
+
+fn group_string(variants: &str, classes: &str) -> String {
+    format!("{}({})", variants, classes)
+}
+
+impl CnUnit {
+    /// Uses the value of a variant field, which constants (`BUTTON.primary`) do not.
+    pub fn references_field(&self) -> bool {
+        let is_field = |arg: &CnFormatArg| match arg {
+            CnFormatArg::Index(_) => true,
+            CnFormatArg::Ident(ident) => !ident.is_constant(),
+        };
+
+        match self {
+            CnUnit::Str(_) | CnUnit::Tuple(_) => false,
+            CnUnit::Int(_) => true,
+            CnUnit::Ident(ident) => !ident.is_constant(),
+            CnUnit::Prefixed(prefixed) => match &prefixed.value {
+                CnPrefixedValue::Int(_) => true,
+                CnPrefixedValue::Ident(ident) => !ident.is_constant(),
+            },
+            CnUnit::Format(format) => format.args.iter().any(is_field),
+            CnUnit::Map(map) => is_field(&map.field),
+        }
+    }
+
+    /// Puts the unit under `variants`, parsed by [`parse_variants`].
+    pub fn with_variants(self, variants: String) -> Self {
+        if variants.is_empty() {
+            return self;
+        }
+
+        match self {
+            CnUnit::Str(literal) => CnUnit::Str(LitStr::new(
+                &group_string(&variants, &literal.value()),
+                literal.span(),
+            )),
+            CnUnit::Tuple(mut tuple) => {
+                tuple.if_cond = group_string(&variants, &tuple.if_cond);
+                tuple.else_cond = tuple
+                    .else_cond
+                    .map(|else_cond| group_string(&variants, &else_cond));
+                CnUnit::Tuple(tuple)
+            }
+            CnUnit::Int(literal) => CnUnit::Prefixed(CnPrefixed {
+                variants,
+                value: CnPrefixedValue::Int(literal),
+            }),
+            CnUnit::Ident(ident) => CnUnit::Prefixed(CnPrefixed {
+                variants,
+                value: CnPrefixedValue::Ident(ident),
+            }),
+            CnUnit::Prefixed(mut prefixed) => {
+                prefixed.variants = format!("{}{}", variants, prefixed.variants);
+                CnUnit::Prefixed(prefixed)
+            }
+            CnUnit::Format(mut format) => {
+                format.template = group_string(&variants, &format.template);
+                CnUnit::Format(format)
+            }
+            CnUnit::Map(mut map) => {
+                for arm in &mut map.arms {
+                    arm.classes = group_string(&variants, &arm.classes);
+                }
+                CnUnit::Map(map)
+            }
+        }
+    }
 }
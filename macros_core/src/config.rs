@@ -0,0 +1,343 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::SystemTime,
+};
+
+use classlist::{prefix_class, ClassOrder, MergeStrategy};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use serde::Deserialize;
+use syn::{Error, Result};
+
+use crate::{stylesheet::stylesheet, utils::global_deps};
+
+pub const CONFIG_FILE: &str = "cln.toml";
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeConfig {
+    #[default]
+    Last,
+    First,
+}
+
+impl From<MergeConfig> for MergeStrategy {
+    fn from(value: MergeConfig) -> Self {
+        match value {
+            MergeConfig::Last => MergeStrategy::Last,
+            MergeConfig::First => MergeStrategy::First,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "kebab-case")]
+pub enum OrderConfig {
+    #[default]
+    Insertion,
+    Tailwind,
+}
+
+impl From<OrderConfig> for ClassOrder {
+    fn from(value: OrderConfig) -> Self {
+        match value {
+            OrderConfig::Insertion => ClassOrder::Insertion,
+            OrderConfig::Tailwind => ClassOrder::Tailwind,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ValidationConfig {
+    pub stylesheets: Vec<PathBuf>,
+    pub safelist: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ManifestConfig {
+    pub dir: PathBuf,
+}
+
+impl Default for ManifestConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("target/cln"),
+        }
+    }
+}
+
+/// Contents of `cln.toml`:
+///
+/// ```toml
+/// prefix = "tw-"
+/// merge = "last" # or "first"
+/// order = "insertion" # or "tailwind"
+///
+/// [validation]
+/// stylesheets = ["dist/app.css"]
+/// safelist = ["js-*"]
+///
+/// [aliases]
+/// btn = "px-4 py-2 rounded"
+///
+/// [manifest] # <crate>.<hash>.classes per call site, <class>.css per `css!` style
+/// dir = "target/cln"
+/// ```
+#[derive(Deserialize, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub prefix: Option<String>,
+    pub merge: MergeConfig,
+    pub order: OrderConfig,
+    pub validation: ValidationConfig,
+    pub aliases: HashMap<String, String>,
+    pub manifest: Option<ManifestConfig>,
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// Every place `cln.toml` is looked up, closest first.
+pub(crate) fn config_candidates(manifest_dir: &Path) -> Vec<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .collect()
+}
+
+fn find_config_file(manifest_dir: &Path) -> Option<PathBuf> {
+    config_candidates(manifest_dir)
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+impl Config {
+    pub fn load(manifest_dir: &Path) -> std::result::Result<Self, String> {
+        let Some(path) = find_config_file(manifest_dir) else {
+            return Ok(Self::default());
+        };
+
+        let content = fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        let mut config: Config = toml::from_str(&content)
+            .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))?;
+
+        let config_dir = path.parent().unwrap_or(manifest_dir);
+        config.validation.stylesheets = config
+            .validation
+            .stylesheets
+            .iter()
+            .map(|stylesheet| config_dir.join(stylesheet))
+            .collect();
+        if let Some(manifest) = config.manifest.as_mut() {
+            manifest.dir = config_dir.join(&manifest.dir);
+        }
+        config.path = Some(path);
+
+        Ok(config)
+    }
+
+    /// Expands aliases and applies the class prefix to a literal class string. Classes
+    /// marked with `raw:` only lose their marker.
+    pub fn transform_classes(&self, classes: &str) -> String {
+        let mut result: Vec<String> = Vec::new();
+
+        for class in classes.split_whitespace() {
+            let expanded = self.aliases.get(class).map_or(class, String::as_str);
+
+            for class in expanded.split_whitespace() {
+                result.push(prefix_class(class, self.prefix()));
+            }
+        }
+
+        result.join(" ")
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or_default()
+    }
+
+    /// Builder call applying the class prefix to dynamic parts, empty without prefix.
+    pub fn prefix_tokens(&self) -> TokenStream {
+        match self.prefix() {
+            "" => TokenStream::new(),
+            prefix => quote! { .prefix(#prefix) },
+        }
+    }
+
+    pub fn merge_strategy(&self) -> MergeStrategy {
+        self.merge.into()
+    }
+
+    /// Builder call selecting the configured merge strategy, empty for the default one.
+    pub fn strategy_tokens(&self) -> TokenStream {
+        let global_dep = global_deps();
+        match self.merge {
+            MergeConfig::Last => TokenStream::new(),
+            MergeConfig::First => quote! { .strategy(#global_dep MergeStrategy::First) },
+        }
+    }
+
+    pub fn class_order(&self) -> ClassOrder {
+        self.order.into()
+    }
+
+    /// Builder call selecting the configured class order, empty for the insertion order.
+    pub fn order_tokens(&self) -> TokenStream {
+        let global_dep = global_deps();
+        match self.order {
+            OrderConfig::Insertion => TokenStream::new(),
+            OrderConfig::Tailwind => quote! { .order(#global_dep ClassOrder::Tailwind) },
+        }
+    }
+
+    /// Sorts literal classes in the configured order.
+    pub fn sort_classes(&self, classes: &str) -> String {
+        let mut classes = classes.split_whitespace().collect::<Vec<&str>>();
+        self.class_order()
+            .sort_prefixed(&mut classes, self.prefix());
+
+        classes.join(" ")
+    }
+}
+
+pub fn manifest_dir() -> PathBuf {
+    PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default())
+}
+
+/// Modification time of every source of a cached value, `None` for missing files.
+type Stamp = Vec<Option<SystemTime>>;
+
+fn stamp(sources: &[PathBuf]) -> Stamp {
+    sources
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+/// Process-wide cache keyed by `CARGO_MANIFEST_DIR`: one proc-macro server may expand
+/// several crates, each with its own configuration.
+pub(crate) type CrateCache<T> = OnceLock<Mutex<HashMap<PathBuf, (Stamp, &'static T)>>>;
+
+/// Value of `cache` for the crate being compiled, computed again whenever one of the
+/// `sources` files is created, changed or removed, since a proc-macro server
+/// (rust-analyzer) outlives such edits. Replaced values are leaked.
+pub(crate) fn per_crate<T>(
+    cache: &CrateCache<T>,
+    sources: &[PathBuf],
+    init: impl FnOnce() -> T,
+) -> &'static T {
+    let mut values = cache
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+
+    let stamp = stamp(sources);
+    match values.get(&manifest_dir()) {
+        Some((cached, value)) if *cached == stamp => value,
+        _ => {
+            let value: &'static T = Box::leak(Box::new(init()));
+            values.insert(manifest_dir(), (stamp, value));
+            value
+        }
+    }
+}
+
+/// Configuration of the crate being compiled, read again when a `cln.toml` changes.
+pub fn config() -> Result<&'static Config> {
+    static CONFIGS: CrateCache<std::result::Result<Config, String>> = OnceLock::new();
+
+    let manifest_dir = manifest_dir();
+    let sources = config_candidates(&manifest_dir);
+    match per_crate(&CONFIGS, &sources, || Config::load(&manifest_dir)) {
+        Ok(config) => Ok(config),
+        Err(message) => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+/// `include_bytes!` of `cln.toml` and every configured stylesheet, so cargo rebuilds the
+/// crate when one of them changes. Every expansion emits it, so it still holds after an
+/// incremental rebuild skipped the others. A `cln.toml` created later cannot be tracked:
+/// touch a source file of the crate, or clean it, to pick it up.
+pub fn track_dependencies() -> TokenStream {
+    let Ok(config) = config() else {
+        return TokenStream::new();
+    };
+    let stylesheet_paths = match stylesheet() {
+        Ok(Some(stylesheet)) => stylesheet.paths(),
+        _ => &[],
+    };
+
+    let paths = config
+        .path
+        .iter()
+        .chain(stylesheet_paths)
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+
+    if paths.is_empty() {
+        return TokenStream::new();
+    }
+
+    quote! { #(const _: &[u8] = include_bytes!(#paths);)* }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_transform() {
+        let config: Config = toml::from_str(
+            r#"
+            prefix = "tw-"
+            merge = "first"
+            order = "tailwind"
+
+            [aliases]
+            btn = "px-4 hover:underline"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(config.merge_strategy(), MergeStrategy::First));
+        assert!(matches!(config.class_order(), ClassOrder::Tailwind));
+        assert_eq!(
+            config.transform_classes("flex btn"),
+            "tw-flex tw-px-4 hover:tw-underline"
+        );
+        assert_eq!(config.transform_classes("raw:js md:raw:host"), "js md:host");
+        assert_eq!(
+            config.sort_classes("tw-p-2 host tw-flex"),
+            "host tw-flex tw-p-2"
+        );
+        assert!(toml::from_str::<Config>("unknown = 1").is_err());
+    }
+
+    #[test]
+    fn per_crate_cache() {
+        static CACHE: CrateCache<usize> = OnceLock::new();
+        let path = env::temp_dir().join(format!("cln-cache-{}.toml", std::process::id()));
+        let sources = [path.clone()];
+
+        let first = per_crate(&CACHE, &sources, || 1);
+        assert!(std::ptr::eq(first, per_crate(&CACHE, &sources, || 2)));
+        assert_eq!(*first, 1);
+
+        fs::write(&path, "").unwrap();
+        let created = *per_crate(&CACHE, &sources, || 3);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(created, 3);
+        assert_eq!(*per_crate(&CACHE, &sources, || 4), 4);
+    }
+}
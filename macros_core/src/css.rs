@@ -0,0 +1,203 @@
+use proc_macro2::{Delimiter, LineColumn, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{Error, Result};
+
+use crate::config::config;
+use crate::manifest::record_style;
+use crate::utils::{global_deps, hash};
+
+/// Writes `tokens` as CSS, keeping the spacing of the source: tokens that touch in the
+/// macro input (`-webkit-box`, `4px`, `#fff`) are written without a space.
+fn write_tokens(tokens: TokenStream, output: &mut String, previous_end: &mut Option<LineColumn>) {
+    for token in tokens {
+        let (start, end) = match &token {
+            TokenTree::Group(group) => (group.span_open().start(), group.span_close().end()),
+            token => (token.span().start(), token.span().end()),
+        };
+        if previous_end.is_some_and(|previous| previous != start) {
+            output.push(' ');
+        }
+
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                output.push_str(open);
+                *previous_end = Some(group.span_open().end());
+                write_tokens(group.stream(), output, previous_end);
+                if previous_end.is_some_and(|previous| previous != group.span_close().start()) {
+                    output.push(' ');
+                }
+                output.push_str(close);
+            }
+            token => output.push_str(&token.to_string()),
+        }
+        *previous_end = Some(end);
+    }
+}
+
+fn css_text(tokens: TokenStream) -> String {
+    let mut output = String::new();
+    write_tokens(tokens, &mut output, &mut None);
+
+    output.trim().to_string()
+}
+
+/// Selector of a nested rule: `&` is replaced by the parent, other selectors become
+/// descendants of it.
+fn nested_selector(parent: &str, selector: &str) -> String {
+    let mut selectors: Vec<String> = Vec::new();
+
+    for parent in parent.split(',') {
+        for part in selector.split(',').map(str::trim) {
+            selectors.push(match part.contains('&') {
+                true => part.replace('&', parent),
+                false => format!("{} {}", parent, part),
+            });
+        }
+    }
+
+    selectors.join(",")
+}
+
+/// Flattens the declarations and nested rules of `tokens` into plain CSS rules for
+/// `selector`. Nested at-rules (`@media ...`) wrap the rules they contain.
+fn flatten(tokens: TokenStream, selector: &str, at_rules: &[String], rules: &mut Vec<String>) {
+    let mut declarations: Vec<String> = Vec::new();
+    let mut nested: Vec<String> = Vec::new();
+    let mut segment = TokenStream::new();
+
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ';' => {
+                declarations.push(css_text(std::mem::take(&mut segment)));
+            }
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                let prelude = css_text(std::mem::take(&mut segment));
+                match prelude.starts_with('@') {
+                    true => {
+                        let mut at_rules = at_rules.to_vec();
+                        at_rules.push(prelude);
+                        flatten(group.stream(), selector, &at_rules, &mut nested);
+                    }
+                    false => flatten(
+                        group.stream(),
+                        &nested_selector(selector, &prelude),
+                        at_rules,
+                        &mut nested,
+                    ),
+                }
+            }
+            token => segment.append(token),
+        }
+    }
+
+    if !segment.is_empty() {
+        declarations.push(css_text(segment));
+    }
+
+    declarations.retain(|declaration| !declaration.is_empty());
+    if !declarations.is_empty() {
+        let rule = format!("{}{{{}}}", selector, declarations.join(";"));
+        rules.push(
+            at_rules
+                .iter()
+                .rev()
+                .fold(rule, |rule, at_rule| format!("{}{{{}}}", at_rule, rule)),
+        );
+    }
+
+    rules.append(&mut nested);
+}
+
+/// Class and rules of a `css! { ... }` block. The class is derived from the content, so
+/// identical blocks share it.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CssTokens {
+    class: String,
+    css: String,
+}
+
+impl TryFrom<TokenStream> for CssTokens {
+    type Error = Error;
+
+    fn try_from(value: TokenStream) -> Result<Self> {
+        let span = value
+            .clone()
+            .into_iter()
+            .next()
+            .map_or_else(Span::call_site, |token| token.span());
+        let class = format!(
+            "{}css-{:08x}",
+            config()?.prefix(),
+            hash(&[&css_text(value.clone())])
+        );
+
+        let mut rules: Vec<String> = Vec::new();
+        flatten(value, &format!(".{}", class), &[], &mut rules);
+        if rules.is_empty() {
+            return Err(Error::new(span, "Expected at least one CSS declaration"));
+        }
+
+        let css = rules.join("");
+        record_style(&class, &css, span)?;
+
+        Ok(Self { class, css })
+    }
+}
+
+impl ToTokens for CssTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self { class, css } = self;
+        let global_dep = global_deps();
+
+        tokens.append_all(quote! {{
+            static STYLE: #global_dep Style = #global_dep Style::new(#class, #css);
+            #global_dep ScopedClass::new(STYLE.register())
+        }});
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn flatten_str(css: &str) -> String {
+        let mut rules: Vec<String> = Vec::new();
+        flatten(css.parse().unwrap(), ".card", &[], &mut rules);
+
+        rules.join("\n")
+    }
+
+    #[test]
+    fn keeps_source_spacing() {
+        let tokens: TokenStream =
+            "display: -webkit-box; margin: 0 auto; color: #fff; width: calc(100% - 4px)"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            css_text(tokens),
+            "display: -webkit-box; margin: 0 auto; color: #fff; width: calc(100% - 4px)"
+        );
+    }
+
+    #[test]
+    fn flattens_nested_rules() {
+        assert_eq!(
+            flatten_str(
+                "padding: 4px; &:hover, &.active { color: red; } .icon { width: 1em } \
+                 @media (min-width: 640px) { padding: 8px; & > a { margin: 0 } }"
+            ),
+            ".card{padding: 4px}\n\
+             .card:hover,.card.active{color: red}\n\
+             .card .icon{width: 1em}\n\
+             @media (min-width: 640px){.card{padding: 8px}}\n\
+             @media (min-width: 640px){.card > a{margin: 0}}"
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Ident, LitStr, Result, Token, Visibility,
+};
+
+use crate::config::{config, manifest_dir};
+use crate::stylesheet::{rename_selector_classes, rewrite_selectors};
+use crate::utils::{global_deps, hash};
+
+const GLOBAL_SELECTOR: &str = ":global(";
+
+/// Generated item: `pub const BUTTON: Button = "button.module.css";`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct CssModuleItem {
+    vis: Visibility,
+    name: Ident,
+    ty: Ident,
+}
+
+/// Input of `css_module!`: a path relative to `CARGO_MANIFEST_DIR`, optionally declared
+/// as a named constant.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CssModuleParser {
+    item: Option<CssModuleItem>,
+    path: LitStr,
+}
+
+impl Parse for CssModuleParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitStr) {
+            return Ok(Self {
+                item: None,
+                path: input.parse()?,
+            });
+        }
+
+        let vis: Visibility = input.parse()?;
+        input.parse::<Token![const]>()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let path: LitStr = input.parse()?;
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+
+        Ok(Self {
+            item: Some(CssModuleItem { vis, name, ty }),
+            path,
+        })
+    }
+}
+
+impl CssModuleParser {
+    /// Declared as a named constant, so it expands to items rather than an expression.
+    pub fn is_item(&self) -> bool {
+        self.item.is_some()
+    }
+}
+
+/// `class` with every character that is not valid unescaped in a CSS class or a Rust
+/// identifier replaced by `_`.
+fn sanitize(class: &str, keep_dash: bool) -> String {
+    let name = class
+        .chars()
+        .map(|c| match c {
+            '-' if keep_dash => c,
+            c if c.is_ascii_alphanumeric() || c == '_' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{}", name),
+        false => name,
+    }
+}
+
+/// Applies `rename` to the classes of `selector`, except inside `:global(...)`, which is
+/// unwrapped and kept as written.
+fn localize_selector(selector: &str, rename: &mut dyn FnMut(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(selector.len());
+    let mut rest = selector;
+
+    while let Some(start) = rest.find(GLOBAL_SELECTOR) {
+        output.push_str(&rename_selector_classes(&rest[..start], rename));
+
+        let inner = &rest[start + GLOBAL_SELECTOR.len()..];
+        let mut depth = 0usize;
+        let end = inner
+            .char_indices()
+            .find(|(_, c)| match c {
+                '(' => {
+                    depth += 1;
+                    false
+                }
+                ')' if depth == 0 => true,
+                ')' => {
+                    depth -= 1;
+                    false
+                }
+                _ => false,
+            })
+            .map_or(inner.len(), |(index, _)| index);
+
+        output.push_str(&inner[..end]);
+        rest = inner.get(end + 1..).unwrap_or_default();
+    }
+
+    output.push_str(&rename_selector_classes(rest, rename));
+    output
+}
+
+/// Locally scoped classes of a CSS module.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct CssModule {
+    /// Original class -> hashed class.
+    classes: BTreeMap<String, String>,
+    css: String,
+}
+
+impl CssModule {
+    /// Rewrites every local class of `css` to `{prefix}{class}_{hash}`, namespaced like the
+    /// utilities.
+    fn new(css: &str, path: &str, prefix: &str) -> Self {
+        let crate_name = env::var("CARGO_PKG_NAME").unwrap_or_default();
+        let mut classes: BTreeMap<String, String> = BTreeMap::new();
+
+        let css = rewrite_selectors(css, &mut |selector| {
+            localize_selector(selector, &mut |class| {
+                let hashed = classes.entry(class.to_string()).or_insert_with(|| {
+                    format!(
+                        "{}{}_{:08x}",
+                        prefix,
+                        sanitize(class, true),
+                        hash(&[&crate_name, path, class])
+                    )
+                });
+
+                Some(hashed.clone())
+            })
+        });
+
+        Self { classes, css }
+    }
+
+    /// Field name of every class. Classes mapping to the same field are an error.
+    fn fields(&self, span: Span) -> Result<Vec<(Ident, &str)>> {
+        let mut names: HashMap<String, &str> = HashMap::new();
+        let mut fields: Vec<(Ident, &str)> = Vec::new();
+
+        for (class, hashed) in &self.classes {
+            let name = sanitize(class, false);
+            if let Some(other) = names.insert(name.clone(), class) {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "Classes `{}` and `{}` both map to the field `{}`",
+                        other, class, name
+                    ),
+                ));
+            }
+
+            // Keywords become raw identifiers (`r#type`), or get a `_` suffix (`self_`).
+            let mut ident = syn::parse_str::<Ident>(&name)
+                .or_else(|_| syn::parse_str::<Ident>(&format!("r#{}", name)))
+                .unwrap_or_else(|_| format_ident!("{}_", name));
+            ident.set_span(span);
+            fields.push((ident, hashed));
+        }
+
+        Ok(fields)
+    }
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct CssModuleTokens {
+    item: Option<CssModuleItem>,
+    path: String,
+    fields: Vec<(Ident, String)>,
+    css: String,
+    span: Span,
+}
+
+impl TryFrom<CssModuleParser> for CssModuleTokens {
+    type Error = Error;
+
+    fn try_from(value: CssModuleParser) -> Result<Self> {
+        let span = value.path.span();
+        let relative = value.path.value();
+        let path = manifest_dir().join(&relative);
+        let css = fs::read_to_string(&path).map_err(|err| {
+            Error::new(
+                span,
+                format!("Failed to read CSS module {}: {}", path.display(), err),
+            )
+        })?;
+
+        let module = CssModule::new(&css, &relative, config()?.prefix());
+        let fields = module
+            .fields(span)?
+            .into_iter()
+            .map(|(name, class)| (name, class.to_string()))
+            .collect();
+
+        Ok(Self {
+            item: value.item,
+            path: path.to_string_lossy().to_string(),
+            fields,
+            css: module.css,
+            span,
+        })
+    }
+}
+
+impl ToTokens for CssModuleTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            item,
+            path,
+            fields,
+            css,
+            span,
+        } = self;
+        let names = fields.iter().map(|(name, _)| name).collect::<Vec<&Ident>>();
+        let classes = fields.iter().map(|(_, class)| class);
+        let global_dep = global_deps();
+
+        let (vis, ty) = match item {
+            Some(item) => (item.vis.to_token_stream(), item.ty.clone()),
+            None => (TokenStream::new(), Ident::new("CssModule", *span)),
+        };
+
+        let definition = quote! {
+            const _: &[u8] = include_bytes!(#path);
+
+            /// Hashed classes of a CSS module, one field per class.
+            #[allow(non_snake_case)]
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #ty {
+                #(pub #names: #global_dep ScopedClass,)*
+            }
+
+            impl #ty {
+                /// Stylesheet with every local class replaced by its hashed name.
+                pub const CSS: &'static str = #css;
+            }
+        };
+        let value = quote! { #ty { #(#names: #global_dep ScopedClass::new(#classes),)* } };
+
+        tokens.append_all(match item {
+            Some(CssModuleItem { name, .. }) => quote! {
+                #definition
+                #vis const #name: #ty = #value;
+            },
+            None => quote! {{
+                #definition
+                #value
+            }},
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hashes_local_classes() {
+        let module = CssModule::new(
+            r#"
+            .button, .button:hover > .icon-left { color: red; }
+            :global(.js-root) .button { content: ".fake"; }
+            @media (min-width: 640px) { .button { padding: 0 } }
+            "#,
+            "button.module.css",
+            "",
+        );
+
+        let button = &module.classes["button"];
+        let icon = &module.classes["icon-left"];
+        assert!(button.starts_with("button_"));
+        assert!(icon.starts_with("icon-left_"));
+        assert_eq!(module.classes.len(), 2);
+        let css = &module.css;
+        assert!(css.contains(&format!(".{}:hover > .{} {{", button, icon)));
+        assert!(css.contains(&format!(" .js-root .{} {{ content: \".fake\"; }}", button)));
+        assert!(css.contains(&format!("{{ .{} {{ padding: 0 }} }}", button)));
+
+        let fields = module.fields(Span::call_site()).unwrap();
+        assert_eq!(fields[1].0, "icon_left");
+    }
+
+    #[test]
+    fn rejects_colliding_fields() {
+        let module = CssModule::new(".a-b {} .a_b {}", "a.module.css", "tw-");
+
+        assert!(module.classes["a-b"].starts_with("tw-a-b_"));
+        assert!(module.fields(Span::call_site()).is_err());
+    }
+}
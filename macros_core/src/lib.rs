@@ -4,35 +4,166 @@ extern crate syn;
 
 // mod fields;
 // mod parsing;
+mod config;
+mod css;
+mod css_module;
+mod manifest;
+mod stylesheet;
 mod utils;
 mod classnames {
+    pub mod lookup;
     pub mod parsers;
     pub mod units;
 }
 
 mod variants {
     pub mod parsers;
+    pub mod props;
+    pub mod rename;
     pub mod units;
 }
 
-use classnames::parsers::CnParser;
-use proc_macro2::TokenStream;
+use classnames::lookup::MAX_LOOKUP_CONDITIONS;
+use classnames::parsers::CnListTokens;
+use proc_macro2::{Span, TokenStream};
 
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 pub use syn::Error;
 use syn::{parse2, Result};
-use variants::parsers::VariantDeriveParser;
+use config::track_dependencies;
+use css::CssTokens;
+use css_module::{CssModuleParser, CssModuleTokens};
+use variants::props::{VariantsParser, VariantsTokens};
+
+pub use classnames::parsers::CnParser;
+pub use classnames::units::CnUnit;
+pub use config::Config;
+pub use stylesheet::{parse_class_selectors, Stylesheet};
+pub use variants::parsers::VariantDeriveParser;
+pub use variants::units::{class_attribute_tokens, ClassAttribute};
+
+fn with_dependencies(result: TokenStream) -> TokenStream {
+    join_dependencies(track_dependencies(), result, false)
+}
+
+/// `result` next to the `dependencies` items: as siblings when `result` is made of items,
+/// inside a block when it is an expression.
+fn join_dependencies(dependencies: TokenStream, result: TokenStream, items: bool) -> TokenStream {
+    match (dependencies.is_empty(), items) {
+        (true, _) => result,
+        (false, true) => quote! { #dependencies #result },
+        (false, false) => quote! {{ #dependencies #result }},
+    }
+}
+
+/// Like [`Error::into_compile_error`], but still a single expression when several
+/// errors were combined.
+pub fn into_expr_compile_error(error: Error) -> TokenStream {
+    let errors = error.into_compile_error();
+    quote! {{ #errors }}
+}
 
 pub fn cn_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
     let result = parse2::<CnParser>(TokenStream::from(input))?
-        .to_cn_tokens()?
+        .into_cn_tokens()?
+        .to_token_stream();
+
+    Ok(with_dependencies(result))
+}
+
+pub fn cn_list_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let result = CnListTokens(parse2::<CnParser>(TokenStream::from(input))?.into_cn_tokens()?)
+        .to_token_stream();
+
+    Ok(with_dependencies(result))
+}
+
+pub fn cn_static_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let result = parse2::<CnParser>(TokenStream::from(input))?
+        .into_static_tokens()?
         .to_token_stream();
 
-    Ok(result)
+    Ok(with_dependencies(result))
+}
+
+pub fn cn_str_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let result = parse2::<CnParser>(TokenStream::from(input))?
+        .into_cn_tokens()?
+        .str_tokens()
+        .ok_or_else(|| {
+            let message = format!(
+                "Only literal classes and up to {} conditional tuples are allowed in a `&'static str` class list",
+                MAX_LOOKUP_CONDITIONS
+            );
+            Error::new(Span::call_site(), message)
+        })?;
+
+    Ok(with_dependencies(result))
 }
 
 pub fn variant_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
     let result = parse2::<VariantDeriveParser>(TokenStream::from(input))?.to_token_stream();
 
-    Ok(result)
+    Ok(join_dependencies(track_dependencies(), result, true))
+}
+
+pub fn css_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let result = CssTokens::try_from(TokenStream::from(input))?.to_token_stream();
+
+    Ok(with_dependencies(result))
+}
+
+pub fn css_module_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let parser = parse2::<CssModuleParser>(TokenStream::from(input))?;
+    let items = parser.is_item();
+    let result = CssModuleTokens::try_from(parser)?.to_token_stream();
+
+    Ok(join_dependencies(track_dependencies(), result, items))
+}
+
+pub fn variants_impl(input: proc_macro::TokenStream) -> Result<TokenStream> {
+    let parser = parse2::<VariantsParser>(TokenStream::from(input))?;
+    let result = VariantsTokens::try_from(parser)?.to_token_stream();
+
+    Ok(join_dependencies(track_dependencies(), result, true))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proc_macro2::{Delimiter, TokenTree};
+    use std::{env, fs, process};
+    use syn::parse_str;
+
+    fn is_block(tokens: TokenStream) -> bool {
+        matches!(
+            tokens.into_iter().collect::<Vec<TokenTree>>().as_slice(),
+            [TokenTree::Group(group)] if group.delimiter() == Delimiter::Brace
+        )
+    }
+
+    #[test]
+    fn css_module_item_keeps_dependencies_as_items() {
+        let path = env::temp_dir().join(format!("cln-module-{}.module.css", process::id()));
+        fs::write(&path, ".button { color: red }").unwrap();
+        let dependencies = quote! { const _: &[u8] = include_bytes!("cln.toml"); };
+
+        let module = |input: String| {
+            let parser = parse_str::<CssModuleParser>(&input).unwrap();
+            let items = parser.is_item();
+            let result = CssModuleTokens::try_from(parser).unwrap().to_token_stream();
+            join_dependencies(dependencies.clone(), result, items)
+        };
+        let item = module(format!(
+            "pub const BUTTON: Button = {:?}",
+            path.to_string_lossy()
+        ));
+        let expr = module(format!("{:?}", path.to_string_lossy()));
+        fs::remove_file(&path).unwrap();
+
+        assert!(item.to_string().starts_with("const _"));
+        assert!(item.to_string().contains("pub const BUTTON : Button"));
+        assert!(!is_block(item));
+        assert!(is_block(expr));
+    }
 }
@@ -0,0 +1,140 @@
+use std::{env, fs, path::PathBuf, process, sync::OnceLock};
+
+use classlist::{ManifestEntry, MANIFEST_EXTENSION, STYLES_EXTENSION};
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+use crate::{
+    config::{config, config_candidates, manifest_dir, per_crate, CrateCache},
+    utils::hash,
+};
+
+/// Directory the class manifests are written to. Enables the manifest even without a
+/// `[manifest]` section in `cln.toml`.
+pub const MANIFEST_DIR_ENV: &str = "CLN_MANIFEST_DIR";
+
+fn configured_dir() -> Result<Option<PathBuf>> {
+    if let Some(dir) = env::var_os(MANIFEST_DIR_ENV) {
+        return Ok(Some(PathBuf::from(dir)));
+    }
+
+    let config = config()?;
+    Ok(config
+        .manifest
+        .as_ref()
+        .map(|manifest| manifest.dir.clone()))
+}
+
+/// Crate name used when cargo does not provide it.
+const UNKNOWN_NAME: &str = "unknown";
+
+fn crate_name() -> String {
+    env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| UNKNOWN_NAME.to_string())
+}
+
+/// Output directory, created on first use, when it is enabled.
+fn output_dir() -> Result<Option<&'static PathBuf>> {
+    static DIRS: CrateCache<std::result::Result<Option<PathBuf>, String>> = OnceLock::new();
+
+    let sources = config_candidates(&manifest_dir());
+    let dir = per_crate(&DIRS, &sources, || {
+        let Some(dir) = configured_dir().map_err(|err| err.to_string())? else {
+            return Ok(None);
+        };
+
+        fs::create_dir_all(&dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+
+        Ok(Some(dir))
+    });
+
+    dir.as_ref()
+        .map(Option::as_ref)
+        .map_err(|message| Error::new(Span::call_site(), message.clone()))
+}
+
+/// Writes `content` to `path`, unless it already holds it. The file is replaced through
+/// a rename, so concurrent builds never leave it half written.
+fn write(path: PathBuf, content: &str, span: Span) -> Result<()> {
+    if fs::read_to_string(&path).is_ok_and(|written| written == content) {
+        return Ok(());
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+    fs::write(&temp, content)
+        .and_then(|()| fs::rename(&temp, &path))
+        .map_err(|err| Error::new(span, format!("Failed to write {}: {}", path.display(), err)))
+}
+
+fn location(span: Span) -> String {
+    let start = span.start();
+    let file = span
+        .local_file()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| span.file());
+
+    format!("{}:{}:{}", file, start.line, start.column + 1)
+}
+
+/// Writes every class of `classes` to the manifest of its call site. Each call site has
+/// its own file, so expanding it again replaces its classes instead of adding to them.
+pub fn record_classes(classes: &str, span: Span) -> Result<()> {
+    let Some(dir) = output_dir()? else {
+        return Ok(());
+    };
+
+    let crate_name = crate_name();
+    let location = location(span);
+
+    let mut content = String::new();
+    for class in classes.split_whitespace() {
+        let entry = ManifestEntry {
+            class: class.to_string(),
+            crate_name: crate_name.clone(),
+            location: location.clone(),
+        };
+        content.push_str(&format!("{}\n", entry));
+    }
+
+    let file_name = format!(
+        "{}.{:08x}.{}",
+        crate_name,
+        hash(&[&location]),
+        MANIFEST_EXTENSION
+    );
+    write(dir.join(file_name), &content, span)
+}
+
+/// Writes the rules of a `css!` class to its own stylesheet. The class is a hash of the
+/// rules, so identical blocks share the file.
+pub fn record_style(class: &str, css: &str, span: Span) -> Result<()> {
+    let Some(dir) = output_dir()? else {
+        return Ok(());
+    };
+
+    let file_name = format!("{}.{}", class, STYLES_EXTENSION);
+    write(dir.join(file_name), &format!("{}\n", css), span)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn call_sites_are_replaced() {
+        let dir = env::temp_dir().join(format!("cln-manifest-write-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("crate.0000abcd.classes");
+
+        write(path.clone(), "flex\n", Span::call_site()).unwrap();
+        write(path.clone(), "flex\n", Span::call_site()).unwrap();
+        write(path.clone(), "grid\n", Span::call_site()).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let files = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(content, "grid\n");
+        assert_eq!(files, 1);
+    }
+}
@@ -0,0 +1,409 @@
+use std::{collections::HashSet, env, fs, path::PathBuf, sync::OnceLock};
+
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+use crate::config::{config, config_candidates, manifest_dir, per_crate, Config, CrateCache};
+
+/// Paths (separated like `PATH`) of extra stylesheets literal classes are checked against,
+/// on top of `validation.stylesheets` from `cln.toml`. Relative paths are resolved from
+/// `CARGO_MANIFEST_DIR`.
+pub const STYLESHEET_ENV: &str = "CLN_STYLESHEET";
+/// Space or comma separated classes that are accepted without a rule, on top of
+/// `validation.safelist`. A trailing `*` accepts every class with that prefix.
+pub const SAFELIST_ENV: &str = "CLN_SAFELIST";
+
+const MAX_SUGGESTIONS: usize = 3;
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Stylesheet {
+    paths: Vec<PathBuf>,
+    classes: HashSet<String>,
+    safelist: Vec<String>,
+}
+
+fn read_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<char> {
+    let mut hex = String::new();
+    while hex.len() < 6 {
+        match chars.peek() {
+            Some(c) if c.is_ascii_hexdigit() => {
+                hex.push(*c);
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+
+    if hex.is_empty() {
+        return chars.next();
+    }
+
+    if chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+
+    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+}
+
+fn is_class_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+/// Class name at the start of `input` (right after its `.`), unescaped, with the number
+/// of bytes it spans. `None` when no valid class starts there.
+fn read_class(input: &str) -> Option<(String, usize)> {
+    let mut chars = input.chars().peekable();
+    let mut class = String::new();
+    let mut escaped_start = false;
+
+    loop {
+        match chars.peek() {
+            Some('\\') => {
+                chars.next();
+                escaped_start |= class.is_empty();
+                if let Some(escaped) = read_escape(&mut chars) {
+                    class.push(escaped);
+                }
+            }
+            Some(c) if is_class_char(*c) => {
+                class.push(*c);
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+
+    let length = input.len() - chars.map(char::len_utf8).sum::<usize>();
+    let starts_with_digit = class.starts_with(|c: char| c.is_ascii_digit());
+    match !class.is_empty() && (escaped_start || !starts_with_digit) {
+        true => Some((class, length)),
+        false => None,
+    }
+}
+
+/// Length of the string or comment starting `input`, if it starts with one.
+fn skipped_length(input: &str) -> Option<usize> {
+    if let Some(comment) = input.strip_prefix("/*") {
+        return Some(comment.find("*/").map_or(input.len(), |end| end + 4));
+    }
+
+    let quote = input.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let mut chars = input.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            c if c == quote => return Some(index + 1),
+            _ => {}
+        }
+    }
+
+    Some(input.len())
+}
+
+/// Calls `rename` with every class of `selector`, replacing the ones it returns a new
+/// name for. Strings, comments and escapes outside class names are kept as written.
+pub(crate) fn rename_selector_classes(
+    selector: &str,
+    rename: &mut dyn FnMut(&str) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(selector.len());
+    let mut rest = selector;
+
+    while let Some(c) = rest.chars().next() {
+        let length = match c {
+            '\\' => rest.chars().nth(1).map_or(1, |next| 1 + next.len_utf8()),
+            '.' => match read_class(&rest[1..]) {
+                Some((class, length)) => {
+                    output.push('.');
+                    match rename(&class) {
+                        Some(name) => output.push_str(&name),
+                        None => output.push_str(&rest[1..length + 1]),
+                    }
+                    rest = &rest[length + 1..];
+                    continue;
+                }
+                None => 1,
+            },
+            _ => skipped_length(rest).unwrap_or(c.len_utf8()),
+        };
+
+        output.push_str(&rest[..length]);
+        rest = &rest[length..];
+    }
+
+    output
+}
+
+/// Replaces the selector of every rule of `css` by the result of `rewrite`. Declarations
+/// and at-rule preludes (`@media ...`) are kept as written.
+pub(crate) fn rewrite_selectors(css: &str, rewrite: &mut dyn FnMut(&str) -> String) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut prelude = 0;
+    let mut index = 0;
+
+    while let Some(c) = css[index..].chars().next() {
+        if let Some(length) = skipped_length(&css[index..]) {
+            index += length;
+            continue;
+        }
+
+        match c {
+            '\\' => index += css[index + 1..].chars().next().map_or(0, char::len_utf8),
+            '{' if !css[prelude..index].trim_start().starts_with('@') => {
+                output.push_str(&rewrite(&css[prelude..index]));
+                output.push('{');
+                prelude = index + 1;
+            }
+            '{' | '}' | ';' => {
+                output.push_str(&css[prelude..=index]);
+                prelude = index + 1;
+            }
+            _ => {}
+        }
+        index += c.len_utf8();
+    }
+
+    output.push_str(&css[prelude..]);
+    output
+}
+
+/// Collects every class used in a selector of `css`. Declarations, comments and
+/// strings are skipped, at-rule preludes (`@media ...`) are ignored.
+pub fn parse_class_selectors(css: &str) -> HashSet<String> {
+    let mut classes: HashSet<String> = HashSet::new();
+
+    rewrite_selectors(css, &mut |selector| {
+        rename_selector_classes(selector, &mut |class| {
+            classes.insert(class.to_string());
+            None
+        })
+    });
+
+    classes
+}
+
+/// Edit distance counting an adjacent transposition (`felx` -> `flex`) as one edit.
+fn distance(first: &str, second: &str) -> usize {
+    let first = first.chars().collect::<Vec<char>>();
+    let second = second.chars().collect::<Vec<char>>();
+    let mut matrix = vec![vec![0; second.len() + 1]; first.len() + 1];
+
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=first.len() {
+        for j in 1..=second.len() {
+            let cost = usize::from(first[i - 1] != second[j - 1]);
+            let mut value = (matrix[i - 1][j] + 1)
+                .min(matrix[i][j - 1] + 1)
+                .min(matrix[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && first[i - 1] == second[j - 2] && first[i - 2] == second[j - 1] {
+                value = value.min(matrix[i - 2][j - 2] + 1);
+            }
+
+            matrix[i][j] = value;
+        }
+    }
+
+    matrix[first.len()][second.len()]
+}
+
+impl Stylesheet {
+    pub fn new(paths: Vec<PathBuf>, safelist: Vec<String>) -> std::result::Result<Self, String> {
+        let mut classes: HashSet<String> = HashSet::new();
+
+        for path in &paths {
+            let css = fs::read_to_string(path)
+                .map_err(|err| format!("Failed to read stylesheet {}: {}", path.display(), err))?;
+            classes.extend(parse_class_selectors(&css));
+        }
+
+        Ok(Self {
+            paths,
+            classes,
+            safelist,
+        })
+    }
+
+    pub fn is_known(&self, class: &str) -> bool {
+        self.classes.contains(class)
+            || self
+                .safelist
+                .iter()
+                .any(|safe| match safe.strip_suffix('*') {
+                    Some(prefix) => class.starts_with(prefix),
+                    None => safe == class,
+                })
+    }
+
+    pub fn suggestions(&self, class: &str) -> Vec<&str> {
+        let max_distance = (class.chars().count() / 3).clamp(1, 3);
+        let mut candidates = self
+            .classes
+            .iter()
+            .map(|known| (distance(class, known), known.as_str()))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect::<Vec<(usize, &str)>>();
+        candidates.sort();
+
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, known)| known)
+            .collect()
+    }
+
+    fn unknown_class_error(&self, class: &str, span: Span) -> Error {
+        let suggestions = self.suggestions(class);
+        let message = match suggestions.as_slice() {
+            [] => format!(
+                "Unknown class `{}`. No rule in the stylesheet defines it",
+                class
+            ),
+            _ => format!(
+                "Unknown class `{}`. Did you mean {}?",
+                class,
+                suggestions
+                    .iter()
+                    .map(|s| format!("`{}`", s))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+
+        Error::new(span, message)
+    }
+
+    pub fn validate(&self, classes: &str, span: Span) -> Result<()> {
+        let mut errors: Option<Error> = None;
+
+        for class in classes.split_whitespace() {
+            if self.is_known(class) {
+                continue;
+            }
+
+            let error = self.unknown_class_error(class, span);
+            match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+
+        errors.map_or(Ok(()), Err)
+    }
+}
+
+fn split_env_list(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Stylesheets from `cln.toml`, extended by the environment variable.
+fn stylesheet_paths(config: &Config) -> Vec<PathBuf> {
+    let mut paths = config.validation.stylesheets.clone();
+
+    if let Some(value) = env::var_os(STYLESHEET_ENV) {
+        let manifest_dir = manifest_dir();
+        paths.extend(
+            env::split_paths(&value)
+                .filter(|path| !path.as_os_str().is_empty())
+                .map(|path| manifest_dir.join(path)),
+        );
+    }
+
+    paths
+}
+
+/// Stylesheets and safelist from `cln.toml`, extended by the environment variables.
+fn load(config: &Config) -> std::result::Result<Option<Stylesheet>, String> {
+    let paths = stylesheet_paths(config);
+    let mut safelist = config.validation.safelist.clone();
+    safelist.extend(split_env_list(SAFELIST_ENV));
+
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    Stylesheet::new(paths, safelist).map(Some)
+}
+
+/// Stylesheet configured for the crate being compiled, read again when it or the
+/// configuration changes.
+pub fn stylesheet() -> Result<Option<&'static Stylesheet>> {
+    static STYLESHEETS: CrateCache<std::result::Result<Option<Stylesheet>, String>> =
+        OnceLock::new();
+
+    let config = config()?;
+    let mut sources = config_candidates(&manifest_dir());
+    sources.extend(stylesheet_paths(config));
+    match per_crate(&STYLESHEETS, &sources, || load(config)) {
+        Ok(stylesheet) => Ok(stylesheet.as_ref()),
+        Err(message) => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+pub fn validate_classes(classes: &str, span: Span) -> Result<()> {
+    match stylesheet()? {
+        Some(stylesheet) => stylesheet.validate(classes, span),
+        None => Ok(()),
+    }
+}
+
+impl Stylesheet {
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stylesheet(css: &str, safelist: &[&str]) -> Stylesheet {
+        Stylesheet {
+            paths: Vec::new(),
+            classes: parse_class_selectors(css),
+            safelist: safelist.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn collects_selector_classes() {
+        let classes = parse_class_selectors(
+            r#"
+            /* .commented { } */
+            .flex, .btn > .icon:hover { display: flex; background: url("a.png"); }
+            @media (min-width: 640px) { .sm\:flex { display: flex } }
+            .w-1\/2 { width: 50% }
+            .\31 0 { order: 1.5 }
+            a[href$=".pdf"] { color: red }
+            "#,
+        );
+
+        let mut classes = classes.into_iter().collect::<Vec<String>>();
+        classes.sort();
+        assert_eq!(classes, ["10", "btn", "flex", "icon", "sm:flex", "w-1/2"]);
+    }
+
+    #[test]
+    fn suggests_similar_classes() {
+        let stylesheet = stylesheet(".flex {} .flex-col {} .grid {}", &["ext-*", "js-hook"]);
+
+        assert_eq!(stylesheet.suggestions("felx"), vec!["flex"]);
+        assert!(stylesheet.is_known("ext-widget"));
+        assert!(stylesheet.is_known("js-hook"));
+        assert!(stylesheet.validate("flex grid", Span::call_site()).is_ok());
+        assert!(stylesheet.validate("flex gird", Span::call_site()).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use quote::{quote, TokenStreamExt};
 use syn::Ident;
 
 pub fn ident_by_num(num: &u8, span: Option<Span>) -> Ident {
-    let span = span.unwrap_or_else(|| Span::call_site());
+    let span = span.unwrap_or_else(Span::call_site);
     let name = format!("var_{}", num);
 
     Ident::new(&name, span)
@@ -16,3 +16,14 @@ pub fn append_separated_coma(target_tokens: &mut TokenStream, list_tokens: &Vec<
 pub(crate) fn global_deps() -> TokenStream {
     quote! {cln::__private::}
 }
+
+/// FNV-1a, stable across compilations and platforms.
+pub(crate) fn hash(parts: &[&str]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in parts.iter().flat_map(|part| part.bytes().chain([0])) {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x01000193);
+    }
+
+    hash
+}
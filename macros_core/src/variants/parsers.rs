@@ -1,20 +1,22 @@
 use proc_macro2::{Punct, Spacing, TokenStream};
-use quote::{quote, ToTokens, TokenStreamExt};
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
 use syn::{
     parse::{Parse, ParseStream},
     spanned::Spanned,
-    Data, DeriveInput, Error, Generics, Ident, Result,
+    Data, DeriveInput, Error, Fields, Generics, Ident, Result, Visibility,
 };
 
 use crate::utils::global_deps;
 
-use super::units::VariantFields;
+use super::units::{ClassContainer, VariantFields};
 
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct VariantDeriveParser {
+    pub vis: Visibility,
     pub name: Ident,
     pub fields: Vec<VariantFields>,
+    pub container: ClassContainer,
 }
 
 fn assert_with_generics(generics: &Generics) -> Result<()> {
@@ -37,16 +39,26 @@ impl Parse for VariantDeriveParser {
         assert_with_generics(&input.generics)?;
         match input.data {
             Data::Enum(data) => {
+                let container = ClassContainer::try_from(input.attrs.as_slice())?;
                 let mut fields: Vec<VariantFields> = Vec::new();
 
                 for variant in &data.variants {
-                    fields.push(variant.clone().try_into()?);
+                    if container.serde && !matches!(variant.fields, Fields::Unit) {
+                        return Err(Error::new(
+                            variant.span(),
+                            "`serde` only supports unit variants",
+                        ));
+                    }
+
+                    fields.push(VariantFields::new(variant, &container)?);
                 }
 
-                return Ok(Self {
+                Ok(Self {
+                    vis: input.vis,
                     name: input.ident,
                     fields,
-                });
+                    container,
+                })
             }
             _ => Err(Error::new(
                 input.span(),
@@ -70,15 +82,204 @@ impl ToTokens for VariantDeriveParser {
 
         let global_dep = global_deps();
         tokens.append_all(quote! {
-            impl Into<#global_dep CnPart> for #name {
-                fn into(self) -> #global_dep CnPart {
+            impl #global_dep IntoClasses for #name {
+                #[allow(unused_variables)]
+                fn append_to(&self, builder: &mut #global_dep CnBuilder) {
                     let variant = match self {
                         #variants
                     };
 
-                    #global_dep CnPart::new(variant)
+                    builder.push_prepared(&variant);
+                }
+            }
+
+            impl ::core::convert::From<#name> for #global_dep CnPart {
+                fn from(value: #name) -> Self {
+                    let mut builder = #global_dep CnBuilder::new();
+                    #global_dep IntoClasses::append_to(&value, &mut builder);
+
+                    #global_dep CnPart::prepared(builder.to_classlist())
+                }
+            }
+        });
+
+        tokens.append_all(self.slots_tokens());
+        tokens.append_all(self.reflection_tokens());
+        if self.container.from_str {
+            tokens.append_all(self.parse_tokens());
+        }
+        if self.container.serde {
+            tokens.append_all(self.serde_tokens());
+        }
+    }
+}
+
+impl VariantDeriveParser {
+    /// `Serialize` and `Deserialize` of the variant names, with the same rename rules as
+    /// `#[class(from_str)]`.
+    fn serde_tokens(&self) -> TokenStream {
+        let name = &self.name;
+        let variants = self
+            .fields
+            .iter()
+            .map(VariantFields::name)
+            .collect::<Vec<&Ident>>();
+        let names = self
+            .fields
+            .iter()
+            .map(VariantFields::renamed)
+            .collect::<Vec<&str>>();
+        let global_dep = global_deps();
+
+        quote! {
+            impl #global_dep serde::Serialize for #name {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: #global_dep serde::Serializer,
+                {
+                    serializer.serialize_str(match self {
+                        #(Self::#variants => #names,)*
+                    })
+                }
+            }
+
+            impl<'de> #global_dep serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: #global_dep serde::Deserializer<'de>,
+                {
+                    let value = <::std::string::String as #global_dep serde::Deserialize>::deserialize(deserializer)?;
+                    match value.as_str() {
+                        #(#names => Ok(Self::#variants),)*
+                        _ => Err(<D::Error as #global_dep serde::de::Error>::unknown_variant(&value, &[#(#names),*])),
+                    }
+                }
+            }
+        }
+    }
+
+    /// `FromStr` and `TryFrom<&str>` of the unit variants, matching their name or their
+    /// exact classes. The first variant wins when a string matches several.
+    fn parse_tokens(&self) -> TokenStream {
+        let name = &self.name;
+        let units = self
+            .fields
+            .iter()
+            .filter(|field| field.is_unit())
+            .collect::<Vec<&VariantFields>>();
+        let valid_values = units.iter().map(|field| field.renamed());
+
+        let mut seen: Vec<&str> = Vec::new();
+        let mut arms: Vec<TokenStream> = Vec::new();
+        for field in &units {
+            let values = [Some(field.renamed()), field.static_string()]
+                .into_iter()
+                .flatten()
+                .filter(|value| !value.is_empty() && !seen.contains(value))
+                .collect::<Vec<&str>>();
+            seen.extend(&values);
+
+            if !values.is_empty() {
+                let variant = field.name();
+                arms.push(quote! { #(#values)|* => Ok(Self::#variant) });
+            }
+        }
+
+        let global_dep = global_deps();
+        quote! {
+            impl ::core::str::FromStr for #name {
+                type Err = #global_dep ParseVariantError;
+
+                fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+                    match value {
+                        #(#arms,)*
+                        _ => Err(#global_dep ParseVariantError::new(value, &[#(#valid_values),*])),
+                    }
                 }
             }
-        })
+
+            impl ::core::convert::TryFrom<&str> for #name {
+                type Error = #global_dep ParseVariantError;
+
+                fn try_from(value: &str) -> ::core::result::Result<Self, Self::Error> {
+                    value.parse()
+                }
+            }
+        }
+    }
+
+    /// `Variant` implementation: variant names and every literal class of the enum.
+    fn reflection_tokens(&self) -> TokenStream {
+        let name = &self.name;
+        let variant_names = self
+            .fields
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect::<Vec<String>>();
+        let variant_idents = self.fields.iter().map(VariantFields::name);
+
+        let mut classes: Vec<&str> = Vec::new();
+        for class in self.fields.iter().flat_map(VariantFields::static_classes) {
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+        }
+
+        let global_dep = global_deps();
+        quote! {
+            impl #global_dep Variant for #name {
+                const VARIANT_NAMES: &'static [&'static str] = &[#(#variant_names),*];
+
+                fn all_static_classes() -> &'static [&'static str] {
+                    &[#(#classes),*]
+                }
+
+                fn variant_name(&self) -> &'static str {
+                    match self {
+                        #(Self::#variant_idents { .. } => #variant_names,)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// `{Name}Slots` struct with one class string per slot, and the `slots()` method
+    /// building it. Nothing when no variant uses `#[class(slot = ...)]`.
+    fn slots_tokens(&self) -> TokenStream {
+        let mut slot_names: Vec<Ident> = Vec::new();
+        for slot in self.fields.iter().flat_map(VariantFields::slot_names) {
+            if !slot_names.contains(slot) {
+                slot_names.push(slot.clone());
+            }
+        }
+
+        if slot_names.is_empty() {
+            return TokenStream::new();
+        }
+
+        let Self {
+            vis, name, fields, ..
+        } = self;
+        let slots_ty = format_ident!("{}Slots", name);
+        let arms = fields
+            .iter()
+            .map(|field| field.slots_arm(&slot_names, &slots_ty));
+
+        quote! {
+            /// Classes of every slot of a variant.
+            #[derive(Clone, Debug, Default, PartialEq, Eq)]
+            #vis struct #slots_ty {
+                #(pub #slot_names: String,)*
+            }
+
+            impl #name {
+                #[allow(unused_variables)]
+                pub fn slots(&self) -> #slots_ty {
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,319 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    parse2,
+    punctuated::Punctuated,
+    Attribute, Error, Ident, LitStr, Path, Result, Token, Visibility,
+};
+
+use crate::classnames::parsers::{CnParser, CnTokens};
+use crate::utils::global_deps;
+
+const BASE_KEY: &str = "base";
+const COMPOUND_KEY: &str = "compound";
+
+/// `intent: Intent = Primary`. Without a default variant, `Default::default()` is used.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct VariantAxis {
+    name: Ident,
+    ty: Path,
+    default: Option<Ident>,
+}
+
+/// `intent: Primary | Danger` inside a compound rule.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct CompoundCondition {
+    axis: Ident,
+    variants: Vec<Ident>,
+}
+
+impl Parse for CompoundCondition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let axis: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let variants = Punctuated::<Ident, Token![|]>::parse_separated_nonempty(input)?;
+
+        Ok(Self {
+            axis,
+            variants: variants.into_iter().collect(),
+        })
+    }
+}
+
+/// `{ intent: Primary, size: Lg } => "uppercase"`.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct CompoundRule {
+    conditions: Vec<CompoundCondition>,
+    classes: LitStr,
+}
+
+impl Parse for CompoundRule {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        braced!(content in input);
+        let conditions = Punctuated::<CompoundCondition, Token![,]>::parse_terminated(&content)?;
+        input.parse::<Token![=>]>()?;
+
+        Ok(Self {
+            conditions: conditions.into_iter().collect(),
+            classes: input.parse()?,
+        })
+    }
+}
+
+/// Input of `variants!`:
+///
+/// ```ignore
+/// variants! {
+///     #[derive(Clone, Copy)]
+///     pub struct Button {
+///         base: ("inline-flex rounded", md: "px-4"),
+///         intent: Intent = Primary,
+///         size: Size = Md,
+///         compound: [
+///             { intent: Primary | Danger, size: Lg } => "uppercase",
+///         ],
+///     }
+/// }
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct VariantsParser {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    base: TokenStream,
+    axes: Vec<VariantAxis>,
+    compound: Vec<CompoundRule>,
+}
+
+impl Parse for VariantsParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+
+        let mut base = TokenStream::new();
+        let mut axes: Vec<VariantAxis> = Vec::new();
+        let mut compound: Vec<CompoundRule> = Vec::new();
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<Token![:]>()?;
+
+            if key == BASE_KEY {
+                base = match content.parse::<TokenTree>()? {
+                    TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                        group.stream()
+                    }
+                    token => token.into_token_stream(),
+                };
+            } else if key == COMPOUND_KEY {
+                let rules;
+                bracketed!(rules in content);
+                compound.extend(Punctuated::<CompoundRule, Token![,]>::parse_terminated(
+                    &rules,
+                )?);
+            } else {
+                let ty: Path = content.parse()?;
+                let default = match content.peek(Token![=]) {
+                    true => {
+                        content.parse::<Token![=]>()?;
+                        Some(content.parse()?)
+                    }
+                    false => None,
+                };
+                axes.push(VariantAxis {
+                    name: key,
+                    ty,
+                    default,
+                });
+            }
+
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            base,
+            axes,
+            compound,
+        })
+    }
+}
+
+impl VariantsParser {
+    fn axis(&self, name: &Ident) -> Result<&VariantAxis> {
+        self.axes
+            .iter()
+            .find(|axis| axis.name == *name)
+            .ok_or_else(|| {
+                Error::new(
+                    name.span(),
+                    format!(
+                        "Unknown variant axis `{}`. Valid values: {}",
+                        name,
+                        self.axes
+                            .iter()
+                            .map(|axis| format!("`{}`", axis.name))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                )
+            })
+    }
+
+    /// `matches!` of every condition of `rule`, joined with `&&`.
+    fn compound_condition(&self, rule: &CompoundRule) -> Result<TokenStream> {
+        let mut conditions: Vec<TokenStream> = Vec::new();
+
+        for condition in &rule.conditions {
+            let VariantAxis { name, ty, .. } = self.axis(&condition.axis)?;
+            let variants = &condition.variants;
+            conditions.push(quote! { matches!(self.#name, #(#ty::#variants)|*) });
+        }
+
+        Ok(match conditions.is_empty() {
+            true => quote! { true },
+            false => quote! { #(#conditions)&&* },
+        })
+    }
+
+    /// Base classes, every axis and every compound rule as one `cn!` list.
+    fn class_tokens(&self) -> Result<(Vec<TokenStream>, CnTokens)> {
+        let mut conditions: Vec<TokenStream> = Vec::new();
+        let mut units: Vec<TokenStream> = Vec::new();
+
+        if !self.base.is_empty() {
+            units.push(self.base.clone());
+        }
+        for axis in &self.axes {
+            let name = &axis.name;
+            units.push(quote! { &self.#name });
+        }
+        for (index, rule) in self.compound.iter().enumerate() {
+            let ident = format_ident!("compound_{}", index);
+            let condition = self.compound_condition(rule)?;
+            let classes = &rule.classes;
+
+            conditions.push(quote! { let #ident = #condition; });
+            units.push(quote! { (#ident, #classes) });
+        }
+
+        let mut tokens = TokenStream::new();
+        tokens.append_separated(units, quote! {,});
+
+        Ok((conditions, parse2::<CnParser>(tokens)?.into_cn_tokens()?))
+    }
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct VariantsTokens {
+    props: VariantsParser,
+    conditions: Vec<TokenStream>,
+    classes: CnTokens,
+}
+
+impl TryFrom<VariantsParser> for VariantsTokens {
+    type Error = Error;
+
+    fn try_from(value: VariantsParser) -> Result<Self> {
+        let (conditions, classes) = value.class_tokens()?;
+
+        Ok(Self {
+            props: value,
+            conditions,
+            classes,
+        })
+    }
+}
+
+impl ToTokens for VariantsTokens {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            props,
+            conditions,
+            classes,
+        } = self;
+        let VariantsParser {
+            attrs,
+            vis,
+            name,
+            axes,
+            ..
+        } = props;
+
+        let fields = axes.iter().map(|VariantAxis { name, ty, .. }| {
+            quote! { #vis #name: #ty }
+        });
+        let defaults = axes
+            .iter()
+            .map(|VariantAxis { name, ty, default }| match default {
+                Some(variant) => quote! { #name: #ty::#variant },
+                None => quote! { #name: ::core::default::Default::default() },
+            });
+        let global_dep = global_deps();
+
+        tokens.append_all(quote! {
+            #(#attrs)*
+            #vis struct #name {
+                #(#fields,)*
+            }
+
+            impl ::core::default::Default for #name {
+                fn default() -> Self {
+                    Self {
+                        #(#defaults,)*
+                    }
+                }
+            }
+
+            impl #name {
+                /// Base classes, the classes of every axis and of the matching compound rules.
+                pub fn class(&self) -> String {
+                    #(#conditions)*
+                    #classes
+                }
+            }
+
+            impl #global_dep IntoClasses for #name {
+                fn append_to(&self, builder: &mut #global_dep CnBuilder) {
+                    builder.push_prepared(&self.class());
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_compound_axis() {
+        let parser = parse2::<VariantsParser>(quote! {
+            pub struct Button {
+                base: "rounded",
+                intent: Intent = Primary,
+                compound: [{ size: Lg } => "uppercase"],
+            }
+        })
+        .unwrap();
+
+        let Err(error) = VariantsTokens::try_from(parser) else {
+            panic!("the compound rule should be rejected");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unknown variant axis `size`. Valid values: `intent`"
+        );
+    }
+}
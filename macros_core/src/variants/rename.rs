@@ -0,0 +1,115 @@
+use syn::{Error, LitStr, Result};
+
+/// Case conversion of `rename_all`, with the names serde uses.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+const RULES: [(&str, RenameRule); 8] = [
+    ("lowercase", RenameRule::Lower),
+    ("UPPERCASE", RenameRule::Upper),
+    ("PascalCase", RenameRule::Pascal),
+    ("camelCase", RenameRule::Camel),
+    ("snake_case", RenameRule::Snake),
+    ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+    ("kebab-case", RenameRule::Kebab),
+    ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+];
+
+impl TryFrom<&LitStr> for RenameRule {
+    type Error = Error;
+
+    fn try_from(value: &LitStr) -> Result<Self> {
+        let name = value.value();
+        RULES
+            .iter()
+            .find(|(rule, _)| *rule == name)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let rules = RULES
+                    .iter()
+                    .map(|(rule, _)| format!("`{}`", rule))
+                    .collect::<Vec<String>>();
+                Error::new(
+                    value.span(),
+                    format!("Unknown rename rule. Valid values: {}", rules.join(", ")),
+                )
+            })
+    }
+}
+
+/// Words of a `PascalCase` variant name: `HttpError2` -> `Http`, `Error2`.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+
+    for c in name.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() && c != '_' => word.push(c),
+            _ if c == '_' => words.push(String::new()),
+            _ => words.push(c.to_string()),
+        }
+    }
+
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+impl RenameRule {
+    /// Applies the rule to a `PascalCase` variant name.
+    pub fn apply(self, name: &str) -> String {
+        let words = words(name);
+        let join = |separator: &str, upper: bool| {
+            words
+                .iter()
+                .map(|word| match upper {
+                    true => word.to_uppercase(),
+                    false => word.to_lowercase(),
+                })
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        match self {
+            RenameRule::Lower => name.to_lowercase(),
+            RenameRule::Upper => name.to_uppercase(),
+            RenameRule::Pascal => name.to_string(),
+            RenameRule::Camel => {
+                let mut chars = name.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            RenameRule::Snake => join("_", false),
+            RenameRule::ScreamingSnake => join("_", true),
+            RenameRule::Kebab => join("-", false),
+            RenameRule::ScreamingKebab => join("-", true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rename_variant_names() {
+        assert_eq!(RenameRule::Kebab.apply("ExtraLarge"), "extra-large");
+        assert_eq!(RenameRule::Kebab.apply("Size2Xl"), "size2-xl");
+        assert_eq!(
+            RenameRule::ScreamingSnake.apply("ExtraLarge"),
+            "EXTRA_LARGE"
+        );
+        assert_eq!(RenameRule::Camel.apply("ExtraLarge"), "extraLarge");
+        assert_eq!(RenameRule::Lower.apply("ExtraLarge"), "extralarge");
+    }
+}
@@ -1,9 +1,12 @@
-use proc_macro2::{Punct, Spacing, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
-use std::{collections::HashSet, num::TryFromIntError};
+use std::{
+    collections::{HashMap, HashSet},
+    num::TryFromIntError,
+};
 use syn::{
-    parse2, spanned::Spanned, Error, Fields, FieldsNamed, FieldsUnnamed, Ident, Meta, Result,
-    Variant,
+    parse2, punctuated::Punctuated, spanned::Spanned, Attribute, Error, Expr, ExprLit, Fields,
+    FieldsNamed, FieldsUnnamed, Ident, Lit, LitStr, Meta, Result, Token, Type, Variant,
 };
 
 use crate::{
@@ -14,25 +17,31 @@ use crate::{
     utils::{append_separated_coma, ident_by_num},
 };
 
+use super::rename::RenameRule;
+
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 struct NamedParam {
     names: HashSet<Ident>,
+    types: HashMap<Ident, Type>,
     used: HashSet<Ident>,
 }
 
 impl From<FieldsNamed> for NamedParam {
     fn from(value: FieldsNamed) -> Self {
         let mut names: HashSet<Ident> = HashSet::new();
+        let mut types: HashMap<Ident, Type> = HashMap::new();
 
         for field in value.named {
             if let Some(ident) = field.ident {
-                names.insert(ident);
+                names.insert(ident.clone());
+                types.insert(ident, field.ty);
             }
         }
 
         Self {
             names,
+            types,
             used: HashSet::new(),
         }
     }
@@ -40,7 +49,7 @@ impl From<FieldsNamed> for NamedParam {
 
 fn get_valid_values_message_by_hashset(names: &HashSet<Ident>) -> String {
     let names_list = names
-        .into_iter()
+        .iter()
         .map(|i| i.to_string())
         .collect::<Vec<String>>();
     format!("Alloved variant values: {:?}", names_list.join(", "))
@@ -59,6 +68,21 @@ impl CheckVariantIdents for NamedParam {
 
         Ok(())
     }
+
+    fn ident_type(&self, value: &CnIdent) -> Option<Type> {
+        // Only a bare field has the declared type, `width.len()` does not.
+        if value.ident != value.sym {
+            return None;
+        }
+
+        self.types.get(&value.ident).cloned()
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        self.names
+            .iter()
+            .any(|field| field.to_string().eq_ignore_ascii_case(name))
+    }
 }
 
 impl ToTokens for NamedParam {
@@ -86,6 +110,7 @@ impl ToTokens for NamedParam {
 #[cfg_attr(feature = "debug", derive(Debug))]
 struct UnnamedParam {
     count: u8,
+    types: Vec<Type>,
     used: HashSet<u8>,
 }
 
@@ -99,6 +124,7 @@ impl TryFrom<FieldsUnnamed> for UnnamedParam {
                 .len()
                 .try_into()
                 .map_err(|err: TryFromIntError| Error::new(value.span(), err.to_string()))?,
+            types: value.unnamed.into_iter().map(|field| field.ty).collect(),
             used: HashSet::new(),
         })
     }
@@ -124,6 +150,10 @@ impl CheckVariantIndexes for UnnamedParam {
         self.used.insert(value);
         Ok(())
     }
+
+    fn index_type(&self, value: u8) -> Option<Type> {
+        self.types.get(usize::from(value)).cloned()
+    }
 }
 
 impl ToTokens for UnnamedParam {
@@ -175,6 +205,20 @@ impl CheckVariantIdents for VariantParams {
             )),
         }
     }
+
+    fn ident_type(&self, value: &CnIdent) -> Option<Type> {
+        match self {
+            VariantParams::Named(named) => named.ident_type(value),
+            _ => None,
+        }
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        match self {
+            VariantParams::Named(named) => named.has_field(name),
+            _ => false,
+        }
+    }
 }
 
 impl CheckVariantIndexes for VariantParams {
@@ -187,6 +231,13 @@ impl CheckVariantIndexes for VariantParams {
             )),
         }
     }
+
+    fn index_type(&self, value: u8) -> Option<Type> {
+        match self {
+            VariantParams::Unnamed(unnamed) => unnamed.index_type(value),
+            _ => None,
+        }
+    }
 }
 
 impl ToTokens for VariantParams {
@@ -202,45 +253,343 @@ impl ToTokens for VariantParams {
 }
 
 
+/// Joins the arguments of every `#[class(...)]` attribute into one `cn!`-like list.
+pub fn class_attribute_tokens(attrs: &[Attribute]) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for attr in attrs {
+        if let Meta::List(list) = &attr.meta {
+            if list.path.to_token_stream().to_string() != "class" {
+                continue;
+            }
+
+            if !list.tokens.is_empty() {
+                if !tokens.is_empty() {
+                    tokens.append(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+                }
+                tokens.append_all(list.tokens.clone());
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Keys of `#[class(key = ...)]` entries that configure the derive instead of naming a
+/// slot.
+const OPTION_KEYS: [&str; 2] = ["rename", "prefix"];
+/// Bare `#[class(key)]` flags.
+const FLAG_KEYS: [&str; 2] = ["skip", "transparent"];
+
+/// Entries of the `#[class(...)]` attributes of a variant.
+#[derive(Default)]
 #[cfg_attr(feature = "debug", derive(Debug))]
-pub struct VariantFields {
-    name: Ident,
-    params: VariantParams,
-    classes: CnTokens,
+pub struct ClassAttribute {
+    /// Plain class units, as one `cn!`-like list.
+    pub units: TokenStream,
+    /// Named slots: `icon = ("w-4", 0)`. A parenthesized value is the list of its units.
+    pub slots: Vec<(Ident, TokenStream)>,
+    /// `rename = "..."` and `prefix = "..."` options, `skip` and `transparent` flags (with
+    /// an empty value).
+    pub options: Vec<(Ident, TokenStream)>,
 }
 
-impl TryFrom<Variant> for VariantFields {
-    type Error = Error;
+impl ClassAttribute {
+    pub fn option(&self, key: &str) -> Option<&(Ident, TokenStream)> {
+        self.options.iter().find(|(name, _)| name == key)
+    }
+}
 
-    fn try_from(value: Variant) -> Result<Self> {
-        let mut params: VariantParams = value.fields.clone().try_into()?;
-        let mut tokens = TokenStream::new();
+impl From<TokenStream> for ClassAttribute {
+    fn from(value: TokenStream) -> Self {
+        let mut entries: Vec<Vec<TokenTree>> = vec![Vec::new()];
+        for token in value {
+            match token {
+                TokenTree::Punct(ref punct) if punct.as_char() == ',' => entries.push(Vec::new()),
+                token => entries.last_mut().unwrap().push(token),
+            }
+        }
 
-        for attr in value.attrs {
-            if let Meta::List(list) = attr.meta {
-                if list.path.to_token_stream().to_string() != "class" {
-                    continue;
+        let mut attribute = Self::default();
+        let mut units: Vec<TokenStream> = Vec::new();
+        for entry in entries.into_iter().filter(|entry| !entry.is_empty()) {
+            match entry.as_slice() {
+                [TokenTree::Ident(name)] if FLAG_KEYS.iter().any(|key| name == key) => {
+                    attribute.options.push((name.clone(), TokenStream::new()));
+                }
+                [TokenTree::Ident(name), TokenTree::Punct(eq), value @ ..]
+                    if eq.as_char() == '=' && eq.spacing() == Spacing::Alone =>
+                {
+                    let value = match value {
+                        [TokenTree::Group(group)]
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            group.stream()
+                        }
+                        value => value.iter().cloned().collect(),
+                    };
+                    match OPTION_KEYS.iter().any(|key| name == key) {
+                        true => attribute.options.push((name.clone(), value)),
+                        false => attribute.slots.push((name.clone(), value)),
+                    }
                 }
+                _ => units.push(entry.into_iter().collect()),
+            }
+        }
 
-                if !list.tokens.is_empty() {
-                    if !tokens.is_empty() {
-                        tokens.append(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+        append_separated_coma(&mut attribute.units, &units);
+        attribute
+    }
+}
+
+/// Flags of the enum attribute, which leave the classes of the variants unchanged.
+const CONTAINER_FLAGS: [&str; 2] = ["from_str", "serde"];
+
+const SERDE_FEATURE_ERROR: &str = "`#[class(serde)]` needs the `serde` feature of `cln`";
+
+/// `#[class(prefix = "btn-", rename_all = "kebab-case")]` on the enum: variants without
+/// classes get `prefix` + their renamed name. `#[class(from_str)]` parses unit variants
+/// back from strings, `#[class(serde)]` (de)serializes them by name.
+#[derive(Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ClassContainer {
+    enabled: bool,
+    prefix: String,
+    rename_all: Option<RenameRule>,
+    pub from_str: bool,
+    pub serde: bool,
+}
+
+impl TryFrom<&[Attribute]> for ClassContainer {
+    type Error = Error;
+
+    fn try_from(value: &[Attribute]) -> Result<Self> {
+        let mut container = Self::default();
+
+        for attr in value.iter().filter(|attr| attr.path().is_ident("class")) {
+            if matches!(attr.meta, Meta::Path(_)) {
+                container.enabled = true;
+                continue;
+            }
+
+            let options = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            for option in options {
+                let option = match option {
+                    Meta::Path(path) if path.is_ident("from_str") => {
+                        container.from_str = true;
+                        continue;
+                    }
+                    Meta::Path(path) if path.is_ident("serde") => {
+                        if !cfg!(feature = "serde") {
+                            return Err(Error::new_spanned(path, SERDE_FEATURE_ERROR));
+                        }
+                        container.serde = true;
+                        continue;
+                    }
+                    Meta::NameValue(option) => option,
+                    option => {
+                        return Err(Error::new_spanned(option.path(), container_option_error()))
                     }
-                    tokens.append_all(list.tokens);
+                };
+
+                container.enabled = true;
+                let value = match &option.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    }) => value,
+                    value => return Err(Error::new_spanned(value, "Expected a string")),
+                };
+
+                if option.path.is_ident("prefix") {
+                    container.prefix = value.value();
+                } else if option.path.is_ident("rename_all") {
+                    container.rename_all = Some(value.try_into()?);
+                } else {
+                    return Err(Error::new_spanned(option.path, container_option_error()));
                 }
             }
         }
 
-        let classes = parse2::<CnParser>(tokens)?
-            .check_idents(&mut params)?
-            .to_cn_tokens()?;
+        Ok(container)
+    }
+}
+
+fn container_option_error() -> String {
+    let options = ["prefix", "rename_all"]
+        .iter()
+        .chain(&CONTAINER_FLAGS)
+        .map(|option| format!("`{}`", option))
+        .collect::<Vec<String>>();
+
+    format!("Unknown option. Valid values: {}", options.join(", "))
+}
+
+impl ClassContainer {
+    /// Name `variant` is parsed from: `#[class(rename = "...")]`, else the name renamed
+    /// with `rename_all`, else the name itself.
+    fn variant_name(&self, variant: &Ident, rename: Option<String>) -> String {
+        rename.unwrap_or_else(|| match self.rename_all {
+            Some(rule) => rule.apply(&variant.to_string()),
+            None => variant.to_string(),
+        })
+    }
+
+    /// Class derived from the variant name, when enabled.
+    fn derived_class(&self, variant: &Ident) -> Option<String> {
+        let rule = self.rename_all.unwrap_or(RenameRule::Kebab);
+
+        self.enabled
+            .then(|| format!("{}{}", self.prefix, rule.apply(&variant.to_string())))
+    }
+}
+
+/// Variant modifiers of `#[class(prefix = "md:")]`, put in front of every field reference.
+fn field_variants(attribute: &ClassAttribute) -> Result<String> {
+    let Some((_, prefix)) = attribute.option("prefix") else {
+        return Ok(String::new());
+    };
+
+    let literal = parse2::<LitStr>(prefix.clone())?;
+    let variants = literal.value().trim().trim_end_matches(':').to_string();
+    if variants.is_empty() || variants.contains(char::is_whitespace) {
+        return Err(Error::new(
+            literal.span(),
+            "Expected variant modifiers: `md:` or `md:hover:`",
+        ));
+    }
+
+    Ok(format!("{}:", variants))
+}
+
+/// Units of `#[class(transparent)]`: the single field of the variant.
+fn transparent_units(value: &Variant, flag: &Ident, units: &TokenStream) -> Result<TokenStream> {
+    if !units.is_empty() {
+        return Err(Error::new(
+            flag.span(),
+            "`transparent` delegates to the field and takes no classes",
+        ));
+    }
+
+    match &value.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(quote! { 0 }),
+        Fields::Named(fields) if fields.named.len() == 1 => {
+            Ok(fields.named[0].ident.to_token_stream())
+        }
+        _ => Err(Error::new(
+            flag.span(),
+            "`transparent` requires a variant with exactly one field",
+        )),
+    }
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct VariantFields {
+    name: Ident,
+    /// Name the variant is parsed from, see [`ClassContainer::variant_name`].
+    renamed: String,
+    params: VariantParams,
+    classes: CnTokens,
+    slots: Vec<(Ident, CnTokens)>,
+}
+
+impl VariantFields {
+    pub fn new(value: &Variant, container: &ClassContainer) -> Result<Self> {
+        let mut params: VariantParams = value.fields.clone().try_into()?;
+        let attribute = ClassAttribute::from(class_attribute_tokens(&value.attrs));
+        let variants = field_variants(&attribute)?;
+        let rename = attribute
+            .option("rename")
+            .map(|(_, rename)| parse2::<LitStr>(rename.clone()))
+            .transpose()?
+            .map(|rename| rename.value());
+
+        let mut units = attribute.units.clone();
+        if let Some((flag, _)) = attribute.option("transparent") {
+            units = transparent_units(value, flag, &units)?;
+        } else if units.is_empty()
+            && attribute.slots.is_empty()
+            && attribute.option("skip").is_none()
+        {
+            let class = match &rename {
+                Some(rename) => Some(format!("{}{}", container.prefix, rename)),
+                None => container.derived_class(&value.ident),
+            };
+            if let Some(class) = class {
+                units = quote! { #class };
+            }
+        }
+
+        let classes = parse2::<CnParser>(units)?
+            .check_idents(&mut params, &variants)?
+            .into_cn_tokens()?;
+
+        let mut slots: Vec<(Ident, CnTokens)> = Vec::new();
+        for (slot, tokens) in attribute.slots {
+            if slots.iter().any(|(name, _)| *name == slot) {
+                return Err(Error::new(
+                    slot.span(),
+                    format!("Slot `{}` is defined twice", slot),
+                ));
+            }
+
+            let classes = parse2::<CnParser>(tokens)?
+                .check_idents(&mut params, &variants)?
+                .into_cn_tokens()?;
+            slots.push((slot, classes));
+        }
 
         Ok(Self {
             name: value.ident.clone(),
+            renamed: container.variant_name(&value.ident, rename),
             params,
             classes,
+            slots,
         })
     }
+
+    pub fn name(&self) -> &Ident {
+        &self.name
+    }
+
+    pub fn renamed(&self) -> &str {
+        &self.renamed
+    }
+
+    pub fn is_unit(&self) -> bool {
+        matches!(self.params, VariantParams::None)
+    }
+
+    /// Classes of the variant when they are fully known at compile time.
+    pub fn static_string(&self) -> Option<&str> {
+        self.classes.single_string().map(String::as_str)
+    }
+
+    /// Literal classes of the variant and of its slots.
+    pub fn static_classes(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(&self.classes)
+            .chain(self.slots.iter().map(|(_, classes)| classes))
+            .flat_map(CnTokens::static_classes)
+    }
+
+    pub fn slot_names(&self) -> impl Iterator<Item = &Ident> {
+        self.slots.iter().map(|(name, _)| name)
+    }
+
+    /// Match arm building the `slots_ty` struct; slots the variant does not define are empty.
+    pub fn slots_arm(&self, slot_names: &[Ident], slots_ty: &Ident) -> TokenStream {
+        let name = &self.name;
+        let params = self.params.to_token_stream();
+        let values = slot_names.iter().map(|slot| {
+            let classes = self.slots.iter().find(|(name, _)| name == slot);
+            match classes {
+                Some((_, classes)) => quote! { #slot: #classes },
+                None => quote! { #slot: String::new() },
+            }
+        });
+
+        quote! { Self:: #name #params => #slots_ty { #(#values,)* } }
+    }
 }
 
 impl ToTokens for VariantFields {
@@ -251,3 +600,73 @@ impl ToTokens for VariantFields {
         tokens.append_all(quote! { Self:: #name #params => #classes});
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::DeriveInput;
+
+    fn fields(variant: TokenStream) -> Result<VariantFields> {
+        VariantFields::new(&parse2::<Variant>(variant)?, &ClassContainer::default())
+    }
+
+    #[test]
+    fn transparent_and_prefixed_fields() {
+        let field = fields(quote! { #[class(transparent, prefix = "md")] Outline(Size) }).unwrap();
+        assert!(field
+            .to_token_stream()
+            .to_string()
+            .contains(r#"Prefixed :: new ("md:" , var_0)"#));
+
+        assert!(fields(quote! { #[class(transparent)] Pair(Size, Size) }).is_err());
+        assert!(fields(quote! { #[class("p-1", transparent)] Plain(Size) }).is_err());
+        assert!(fields(quote! { #[class(0, prefix = "md hover:")] Plain(Size) }).is_err());
+    }
+
+    #[test]
+    fn constants_and_fields() {
+        let field = quote! { #[class(size, BUTTON.primary, self::SIZE)] Plain { size: Size } };
+        assert!(fields(field).is_ok());
+
+        let Err(error) = fields(quote! { #[class(SIZE)] Plain { size: Size } }) else {
+            panic!("`SIZE` should name the field");
+        };
+        assert!(error.to_string().contains("size"));
+    }
+
+    fn container(attr: TokenStream) -> Result<ClassContainer> {
+        let input = parse2::<DeriveInput>(quote! { #attr enum Size {} })?;
+        ClassContainer::try_from(input.attrs.as_slice())
+    }
+
+    #[test]
+    fn container_flags() {
+        let from_str = container(quote! { #[class(from_str)] }).unwrap();
+        assert!(from_str.from_str && !from_str.enabled);
+
+        let renamed = container(quote! { #[class(rename_all = "snake_case")] }).unwrap();
+        let ident = Ident::new("ExtraLarge", Span::call_site());
+        assert_eq!(renamed.variant_name(&ident, None), "extra_large");
+
+        let Err(error) = container(quote! { #[class(json)] }) else {
+            panic!("`json` should be rejected");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unknown option. Valid values: `prefix`, `rename_all`, `from_str`, `serde`"
+        );
+    }
+
+    #[test]
+    fn serde_needs_the_feature() {
+        let serde = container(quote! { #[class(serde)] });
+        if cfg!(feature = "serde") {
+            assert!(serde.is_ok_and(|serde| serde.serde));
+        } else {
+            let Err(error) = serde else {
+                panic!("`serde` should need the feature");
+            };
+            assert_eq!(error.to_string(), SERDE_FEATURE_ERROR);
+        }
+    }
+}
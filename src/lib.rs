@@ -1,7 +1,48 @@
+#[cfg(feature = "serde")]
+pub use classlist::as_tokens;
+pub use classlist::collect_style_tag;
+pub use classlist::collect_styles;
+pub use classlist::diff_cnl;
+pub use classlist::registered_styles;
+pub use classlist::sort_cnl;
+pub use classlist::style_tag;
+pub use classlist::ClassDiff;
+pub use classlist::ClassList;
+pub use classlist::ClassOrder;
+#[cfg(feature = "serde")]
+pub use classlist::ClassTokens;
+pub use classlist::CnBuilder;
+pub use classlist::Condition;
+pub use classlist::Important;
+pub use classlist::IntoClasses;
+pub use classlist::MergeStrategy;
+pub use classlist::ParseVariantError;
+pub use classlist::Prefixed;
+pub use classlist::Responsive;
+pub use classlist::ScopedClass;
+pub use classlist::Variant;
 pub use macros::cn;
+pub use macros::cn_list;
+pub use macros::cn_static;
+pub use macros::cn_str;
+pub use macros::css;
+pub use macros::css_module;
+pub use macros::variants;
 pub use macros::Variant;
 
 pub mod __private {
+    pub use classlist::ClassList;
+    pub use classlist::ClassOrder;
     pub use classlist::CnBuilder;
     pub use classlist::CnPart;
+    pub use classlist::Condition;
+    pub use classlist::IntoClasses;
+    pub use classlist::MergeStrategy;
+    pub use classlist::ParseVariantError;
+    pub use classlist::Prefixed;
+    pub use classlist::ScopedClass;
+    pub use classlist::Style;
+    pub use classlist::Variant;
+    #[cfg(feature = "serde")]
+    pub use serde;
 }
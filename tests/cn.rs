@@ -0,0 +1,42 @@
+use cln::{cn, cn_static, cn_str};
+
+fn classes(classes: String) -> String {
+    classes
+}
+
+#[test]
+fn cn_is_always_a_string() {
+    let active = true;
+    let size = "p-4";
+
+    let line = if active {
+        cn!("a", (active, "b"))
+    } else {
+        cn!("c", size)
+    };
+
+    assert_eq!(line, "a b");
+    assert_eq!(classes(cn!("a", (active, "b", "c"))), "a b");
+}
+
+#[test]
+fn cn_str_is_not_allocated() {
+    let active = false;
+    let line: &'static str = cn_str!("a", (active, "b", "c"));
+
+    assert_eq!(line, "a c");
+    assert_eq!(cn_str!("a", "b"), "a b");
+    assert_eq!(cn_str!(), "");
+}
+
+const CARD: &str = cn_static!("rounded p-4", (true, "shadow"), (false, "border"));
+static BUTTON: &str = cn_static!("btn", 2, "p-2");
+
+#[test]
+fn cn_static_is_const() {
+    const LEN: usize = CARD.len();
+
+    assert_eq!(CARD, "rounded p-4 shadow");
+    assert_eq!(LEN, 18);
+    assert_eq!(BUTTON, "btn 2 p-2");
+}
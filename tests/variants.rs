@@ -0,0 +1,208 @@
+use cln::{cn, variants, Variant};
+
+#[derive(Variant, Default)]
+pub enum Intent {
+    #[default]
+    #[class("bg-blue-500")]
+    Primary,
+    #[class("bg-red-500")]
+    Danger,
+    #[class("bg-gray-100")]
+    Ghost,
+}
+
+#[derive(Variant, Default)]
+pub enum Size {
+    #[class("text-sm")]
+    Sm,
+    #[default]
+    #[class("text-lg")]
+    Lg,
+}
+
+variants! {
+    pub struct Button {
+        base: "inline-flex rounded",
+        intent: Intent = Danger,
+        size: Size,
+        compound: [
+            { intent: Primary | Danger, size: Lg } => "uppercase",
+            {} => "shadow",
+        ],
+    }
+}
+
+variants! {
+    struct Badge {
+        size: Size = Sm,
+    }
+}
+
+#[test]
+fn defaults() {
+    let button = Button::default();
+
+    assert!(matches!(button.intent, Intent::Danger));
+    assert!(matches!(button.size, Size::Lg));
+    assert!(matches!(Badge::default().size, Size::Sm));
+}
+
+#[test]
+fn class() {
+    let button = Button {
+        intent: Intent::Ghost,
+        size: Size::Sm,
+    };
+
+    assert_eq!(
+        button.class(),
+        "inline-flex rounded bg-gray-100 text-sm shadow"
+    );
+    assert_eq!(Badge::default().class(), "text-sm");
+}
+
+#[test]
+fn compound_rules() {
+    let primary = Button {
+        intent: Intent::Primary,
+        size: Size::Lg,
+    };
+    let small = Button {
+        intent: Intent::Primary,
+        size: Size::Sm,
+    };
+
+    assert_eq!(
+        Button::default().class(),
+        "inline-flex rounded bg-red-500 text-lg uppercase shadow"
+    );
+    assert_eq!(
+        primary.class(),
+        "inline-flex rounded bg-blue-500 text-lg uppercase shadow"
+    );
+    assert!(!small.class().contains("uppercase"));
+}
+
+#[test]
+fn into_classes() {
+    let button = Button::default();
+
+    assert_eq!(
+        cn!(button, "mt-2"),
+        "inline-flex rounded bg-red-500 text-lg uppercase shadow mt-2"
+    );
+}
+
+#[test]
+fn into_part() {
+    use cln::{__private::CnPart, CnBuilder};
+
+    let part: CnPart = Intent::Danger.into();
+    let line = CnBuilder::new()
+        .prefix("tw-")
+        .add("flex")
+        .add(part)
+        .add(CnPart::from(Size::Sm).prefixed("md:"))
+        .to_classlist();
+
+    assert_eq!(line, "tw-flex bg-red-500 md:text-sm");
+}
+
+#[test]
+fn reflection() {
+    use cln::Variant as _;
+
+    #[derive(Variant)]
+    enum Field {
+        #[class("border", (active, "ring-2", "ring-0"), icon = ("w-4 h-4", (active, "opacity-50")))]
+        Outlined { active: bool },
+        #[class("p-2", width => { 1: "w-1", 2 | 3: "w-2", _: "w-full" })]
+        Sized { width: u8 },
+        #[class(0, "border")]
+        Nested(Size),
+    }
+
+    assert_eq!(Field::VARIANT_NAMES, ["Outlined", "Sized", "Nested"]);
+    assert_eq!(
+        Field::all_static_classes(),
+        [
+            "border",
+            "ring-2",
+            "ring-0",
+            "w-4",
+            "h-4",
+            "opacity-50",
+            "p-2",
+            "w-1",
+            "w-2",
+            "w-full"
+        ]
+    );
+    let outlined = Field::Outlined { active: true };
+    assert_eq!(outlined.variant_name(), "Outlined");
+    assert_eq!(Field::Sized { width: 2 }.variant_name(), "Sized");
+    assert_eq!(Field::Nested(Size::Sm).variant_name(), "Nested");
+    assert_eq!(outlined.slots().icon, "w-4 h-4 opacity-50");
+}
+
+#[test]
+fn from_str() {
+    #[derive(Variant)]
+    #[class(from_str, rename_all = "kebab-case")]
+    enum Tone {
+        #[class("text-gray-900", rename = "dark")]
+        DarkGray,
+        #[class("text-white")]
+        White,
+        // Same classes as `White`, which is declared first.
+        #[class("text-white")]
+        Snow,
+        #[class("text-gray-500", size)]
+        Muted { size: Size },
+    }
+
+    assert!(matches!("dark".parse(), Ok(Tone::DarkGray)));
+    assert!(matches!("text-gray-900".parse(), Ok(Tone::DarkGray)));
+    assert!(matches!(Tone::try_from("white"), Ok(Tone::White)));
+    assert!(matches!(Tone::try_from("text-white"), Ok(Tone::White)));
+    assert!(matches!(Tone::try_from("snow"), Ok(Tone::Snow)));
+    // Variants with fields are not parsed, even by their classes.
+    let muted = Tone::Muted { size: Size::Sm };
+    assert_eq!(cn!(muted), "text-gray-500 text-sm");
+
+    for value in ["dark-gray", "DarkGray", "muted", "text-gray-900 "] {
+        let Err(error) = value.parse::<Tone>() else {
+            panic!("{value:?} should match no variant");
+        };
+        assert_eq!(error.value(), value);
+        assert_eq!(error.valid_values(), ["dark", "white", "snow"]);
+    }
+    assert_eq!(
+        Tone::try_from("black").err().unwrap().to_string(),
+        r#"Unknown variant "black". Valid values: dark, white, snow"#
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    #[derive(Variant, Debug, PartialEq)]
+    #[class(serde, rename_all = "kebab-case")]
+    enum Tone {
+        #[class("text-gray-900")]
+        DarkGray,
+        #[class("text-white")]
+        White,
+    }
+
+    let json = serde_json::to_string(&[Tone::DarkGray, Tone::White]).unwrap();
+    assert_eq!(json, r#"["dark-gray","white"]"#);
+
+    let tones: Vec<Tone> = serde_json::from_str(&json).unwrap();
+    assert!(tones == [Tone::DarkGray, Tone::White]);
+
+    let error = serde_json::from_str::<Tone>(r#""black""#).err().unwrap();
+    assert!(error
+        .to_string()
+        .starts_with("unknown variant `black`, expected `dark-gray` or `white`"));
+}